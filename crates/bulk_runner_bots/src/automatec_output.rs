@@ -0,0 +1,132 @@
+use std::fmt::{self, Display};
+
+use serde::{Serialize, Serializer};
+
+/// Why `AutomateC` refused to start a process, as read from what it printed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FailureReason {
+    /// The resource is already running a session.
+    ResourceBusy,
+    /// The resource is offline, not connected, or unknown to Blue Prism.
+    ResourceUnavailable,
+    /// No process goes by the name given to `/run`.
+    ProcessNotFound,
+    /// The process exists, but isn't published, so can't be run from `AutomateC`.
+    NotPublished,
+    /// The user isn't allowed to run the process, or on the resource.
+    PermissionDenied,
+    /// Nothing recognised, holding the first line `AutomateC` printed (if any).
+    Unrecognised(String),
+}
+
+/// Lowercased phrases `AutomateC` uses for each reason, checked in order.
+const REASONS: &[(&[&str], FailureReason)] = &[
+    (&["not published"], FailureReason::NotPublished),
+    (
+        &[
+            "permission",
+            "not authorised",
+            "not authorized",
+            "access denied",
+            "access is denied",
+            "unauthorised",
+            "unauthorized",
+        ],
+        FailureReason::PermissionDenied,
+    ),
+    (
+        &[
+            "process not found",
+            "no process",
+            "process does not exist",
+            "could not find process",
+            "unknown process",
+        ],
+        FailureReason::ProcessNotFound,
+    ),
+    (&["busy", "already running", "pending session"], FailureReason::ResourceBusy),
+    (
+        &[
+            "not connected",
+            "offline",
+            "not available",
+            "unavailable",
+            "resource not found",
+            "no resource",
+            "could not find resource",
+            "unknown resource",
+        ],
+        FailureReason::ResourceUnavailable,
+    ),
+];
+
+impl FailureReason {
+    /// Classifies a failed run from its captured stdout and stderr,
+    /// falling back to the first line printed (stderr first) when no phrase is recognised.
+    #[must_use]
+    pub fn classify(stdout: &str, stderr: &str) -> Self {
+        let printed = format!("{stderr}\n{stdout}").to_lowercase();
+
+        REASONS
+            .iter()
+            .find(|(phrases, _)| phrases.iter().any(|phrase| printed.contains(phrase)))
+            .map_or_else(
+                || {
+                    let first_line = [stderr, stdout]
+                        .into_iter()
+                        .flat_map(str::lines)
+                        .map(str::trim)
+                        .find(|line| !line.is_empty())
+                        .unwrap_or_default();
+                    FailureReason::Unrecognised(first_line.to_string())
+                },
+                |(_, reason)| reason.clone(),
+            )
+    }
+}
+
+impl Display for FailureReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FailureReason::ResourceBusy => f.write_str("resource busy"),
+            FailureReason::ResourceUnavailable => f.write_str("resource unavailable"),
+            FailureReason::ProcessNotFound => f.write_str("process not found"),
+            FailureReason::NotPublished => f.write_str("process not published"),
+            FailureReason::PermissionDenied => f.write_str("permission denied"),
+            FailureReason::Unrecognised(line) if line.is_empty() => f.write_str("no reason given"),
+            FailureReason::Unrecognised(line) => f.write_str(line),
+        }
+    }
+}
+
+impl Serialize for FailureReason {
+    /// Serialized as it is displayed, e.g. "resource busy".
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Finds the session ID `AutomateC` prints once `/run` has started a session.
+///
+/// That is the first GUID following the word "session", or failing that the first GUID printed at all.
+#[must_use]
+pub fn parse_session_id(stdout: &str) -> Option<String> {
+    let after_session = stdout
+        .to_ascii_lowercase()
+        .find("session")
+        .map_or("", |at| &stdout[at..]);
+
+    first_guid(after_session).or_else(|| first_guid(stdout))
+}
+
+/// The first `8-4-4-4-12` hex GUID in `text`, without any braces around it.
+fn first_guid(text: &str) -> Option<String> {
+    text.split(|c: char| !(c.is_ascii_hexdigit() || c == '-'))
+        .find(|token| is_guid(token))
+        .map(str::to_string)
+}
+
+fn is_guid(token: &str) -> bool {
+    let groups = token.split('-').map(str::len).collect::<Vec<_>>();
+    groups == [8, 4, 4, 4, 12]
+}
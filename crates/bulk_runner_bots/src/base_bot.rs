@@ -1,20 +1,44 @@
+use std::collections::BTreeMap;
+
 use deadpool_tiberius::tiberius::Row;
+use serde::Serialize;
 
 use crate::bot_types::{BotStatus, BotStatusNotReady, BotStatusReady};
+use crate::column_map::ColumnMap;
+use crate::{Error, Result};
 
-#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash, Serialize)]
 pub struct Bot {
     pub name:   String,
     pub status: BotStatus,
+    /// Values of the `ColumnMap` extra columns the query returned for this bot.
+    pub extras: BTreeMap<String, String>,
 }
 
 impl Bot {
+    /// A bot with no extra columns, the name is uppercased like a queried one.
+    #[must_use]
+    pub fn new(name: impl AsRef<str>, status: impl Into<BotStatus>) -> Self {
+        Bot {
+            name:   name.as_ref().to_uppercase(),
+            status: status.into(),
+            extras: BTreeMap::new(),
+        }
+    }
+
     #[must_use]
     #[inline]
     pub fn name(&self) -> &str {
         &self.name
     }
 
+    /// The value of the extra column `column`, if the query returned one for this bot.
+    #[must_use]
+    #[inline]
+    pub fn extra(&self, column: &str) -> Option<&str> {
+        self.extras.get(column).map(String::as_str)
+    }
+
     #[must_use]
     #[inline]
     pub fn is_logged_out(&self) -> bool {
@@ -49,26 +73,79 @@ impl Bot {
 pub struct BaseBot {
     pub(crate) name:   Option<String>,
     pub(crate) status: Option<String>,
+    pub(crate) extras: BTreeMap<String, String>,
 }
 
-impl From<&Row> for BaseBot {
+impl BaseBot {
+    /// A bot as read from any source, a `None` status becomes an unavailable `Bot`.
+    #[must_use]
+    pub fn new(name: impl Into<String>, status: Option<String>) -> Self {
+        BaseBot {
+            name: Some(name.into()),
+            status,
+            extras: BTreeMap::new(),
+        }
+    }
+
+    #[must_use]
     #[inline]
-    fn from(row: &Row) -> Self {
-        let name: String = row
-            .try_get(0)
-            .unwrap_or(Some("FailedToGet"))
-            .unwrap_or_default()
-            .to_string();
-
-        let status = row
-            .try_get(1)
-            .unwrap_or(Some("FailedToGet"))
-            .unwrap_or_default()
-            .to_string();
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
 
+    #[must_use]
+    pub fn with_extra(mut self, column: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extras.insert(column.into(), value.into());
+        self
+    }
+
+    /// Reads a bot from `row`, by the column names in `columns`.
+    ///
+    /// A NULL status is kept, and becomes an unavailable `Bot`.
+    ///
+    /// # Errors
+    /// Returns an error if the name or status column is missing, any mapped column isn't text,
+    /// or the name is NULL or blank.
+    pub fn try_from_row(row: &Row, columns: &ColumnMap) -> Result<Self> {
+        let name = ColumnMap::get(row, columns.name())?
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| Error::NullColumn(columns.name().to_string()))?;
+
+        let status = ColumnMap::get(row, columns.status())?;
+
+        let mut extras = BTreeMap::new();
+        for extra in columns.extras() {
+            if let Some(value) = ColumnMap::get_optional(row, extra)? {
+                extras.insert(extra.clone(), value.to_string());
+            }
+        }
+
+        Ok(BaseBot {
+            name: Some(name.to_string()),
+            status: status.map(str::to_string),
+            extras,
+        })
+    }
+}
+
+impl TryFrom<&Row> for BaseBot {
+    type Error = Error;
+
+    /// Reads a bot from the `name` and `status` columns of `row`.
+    #[inline]
+    fn try_from(row: &Row) -> Result<Self> {
+        BaseBot::try_from_row(row, &ColumnMap::default())
+    }
+}
+
+impl From<Bot> for BaseBot {
+    #[inline]
+    fn from(bot: Bot) -> Self {
         BaseBot {
-            name:   Some(name),
-            status: Some(status),
+            name:   Some(bot.name),
+            status: Some(String::from(bot.status)),
+            extras: bot.extras,
         }
     }
 }
@@ -86,7 +163,11 @@ impl From<BaseBot> for Bot {
             None => BotStatus::NotReady(BotStatusNotReady::Unavailable),
         };
 
-        Bot { name, status }
+        Bot {
+            name,
+            status,
+            extras: base_bot.extras,
+        }
     }
 }
 
@@ -98,6 +179,7 @@ impl From<&BaseBot> for Bot {
         Bot {
             name:   name.to_uppercase(),
             status: status.into(),
+            extras: value.extras.clone(),
         }
     }
 }
@@ -1,26 +1,37 @@
 use std::fmt::Display;
-use std::process::Output;
+use std::path::Path;
+use std::process::{Output, Stdio};
 
 use tokio::process::{Child, Command};
 
-use crate::{debug, error, info, Error, Result};
+use crate::dispatch_outcome::DispatchOutcome;
+use crate::{debug, error, info, Error, RedactedArgs, Result};
 
 /// Function called per-dispatch to spawn a child process to run the bot
 ///
-/// # Errors
-/// Returns an error if the child process fails to spawn
+/// `exe_path` is the `AutomateC` executable to spawn,
+/// the returned `DispatchOutcome` is built from the child's exit status and captured output.
+/// The child is killed if this future is dropped before it exits, which is how dispatch timeouts stop it.
 ///
-/// # Panics
-/// Will panic if the child process fails to spawn
+/// # Errors
+/// Returns `Error::Spawn` if the executable can't be started (it is missing, say),
+/// `Error::Wait` if the child can't be waited on, and `Error::ChildProcessSpawnFailed`
+/// or `Error::Tokio` if the child never made it back from the blocking spawn.
 pub async fn dispatch(
+    exe_path: impl AsRef<Path>,
     name: impl AsRef<str> + Display + Send + 'static,
     commander: Vec<String>,
-) -> Result<()> {
+) -> Result<DispatchOutcome> {
     let (tx_stop, rx_stop) = tokio::sync::oneshot::channel();
 
-    let cmd = Command::new(&*crate::DEFAULT_EXE_PATH);
+    let mut cmd = Command::new(exe_path.as_ref());
+    // Captured, so the output ends up in the dispatch report rather than interleaved on our stdout
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
 
-    spawn_child_proc(tx_stop, cmd, commander).await;
+    spawn_child_proc(tx_stop, cmd, exe_path.as_ref(), commander).await?;
 
     let child = match rx_stop.await {
         Ok(c) => {
@@ -30,61 +41,41 @@ pub async fn dispatch(
         Err(e) => return Err(Error::ChildProcessSpawnFailed(e)),
     };
 
-    let output = tokio::task::spawn(async move {
-        let after = child.wait_with_output().await.map_err(Error::from).unwrap();
-        CheckStatus::from(after).check_status(name);
-    });
-
-    tokio::pin!(output);
-    let _ = (&mut output).await;
-
-    // CheckStatus::from(after).check_status(name);
+    // Awaited here rather than in a spawned task, so a timed out dispatch drops (and kills) the child
+    let after = child.wait_with_output().await.map_err(|source| {
+        Error::Wait {
+            resource: name.to_string(),
+            source,
+        }
+    })?;
+    let status = CheckStatus::from(after);
+    status.check_status(name);
 
-    Ok(())
+    Ok(DispatchOutcome::from(status))
 }
 
 async fn spawn_child_proc(
     tx_stop: tokio::sync::oneshot::Sender<Child>,
     mut cmd: Command,
+    exe_path: &Path,
     commander: Vec<String>,
-) {
-    debug!("->> {:<12} - {:?}", "DISPATCH:: Commander", &commander);
+) -> Result<()> {
+    debug!("->> {:<12} - {:?}", "DISPATCH:: Commander", RedactedArgs::new(&commander));
     info!("{:<12}", "DISPATCH:: Child proc");
 
-    let cmd_for_print = commander.clone();
-    debug!("->> {:<12} - {:?}", "DISPATCH:: Commander", &cmd_for_print);
-
-    // match tokio::spawn(async move {
+    let exe_path = exe_path.to_path_buf();
     tokio::task::spawn_blocking(move || {
         info!("->> {:<12} - {}", "DISPATCH:: Spawned", "Child Proc");
-        let child = cmd
-            .args(commander.clone())
-            .spawn()
-            .map_err(crate::error::Error::from)
-            .expect("Failed to spawn");
-
-        tx_stop.send(child).unwrap();
+        let child = cmd.args(commander).spawn().map_err(|source| {
+            error!("->> {:<12} - {}: {} - {source}", "DISPATCH:: ERR", "Could not spawn", exe_path.display());
+            Error::Spawn { exe_path, source }
+        })?;
+
+        // The dispatch may have timed out already, dropping the child kills it
+        let _ = tx_stop.send(child);
+        Ok(())
     })
-    .await
-    .unwrap();
-
-    // .unwrap()
-    // .await;
-    // .expect("Failed to wait on child");
-
-    // info!("Before match output");
-
-    // let output = match output {
-    //     Ok(o) => {
-    //         info!("->> {:<12} - {}", "DISPATCH:: OK", "Task dispatched!");
-    //         o
-    //     }
-    //     Err(e) => return error!("->> {:<12} - Tried to run {cmd_for_print:?} - {e}", "DISPATCH:: ERR"),
-    // };
-
-    // tx_stop.send(output).unwrap();
-    // })
-    // .await
+    .await?
 }
 
 enum CheckStatus {
@@ -103,6 +94,16 @@ impl From<Output> for CheckStatus {
     }
 }
 
+impl From<CheckStatus> for DispatchOutcome {
+    #[inline]
+    fn from(status: CheckStatus) -> Self {
+        match status {
+            CheckStatus::Success(output) => DispatchOutcome::Started(output.into()),
+            CheckStatus::Fail(output) => DispatchOutcome::Failed(output.into()),
+        }
+    }
+}
+
 impl CheckStatus {
     #[inline]
     fn check_status(&self, name: impl AsRef<str> + Display) {
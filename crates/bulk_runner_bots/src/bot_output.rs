@@ -1,15 +1,36 @@
 use std::fmt::{self, Debug, Display};
 use std::process::{ExitStatus, Output};
 
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::automatec_output::{parse_session_id, FailureReason};
+
 #[derive(Debug, Default)]
 pub struct BotOutput {
-    inner_buf: Vec<u8>,
-    stdout:    String,
-    stderr:    String,
-    status:    ExitStatus,
+    inner_buf:  Vec<u8>,
+    stdout:     String,
+    stderr:     String,
+    status:     ExitStatus,
+    session_id: Option<String>,
+    failure:    Option<FailureReason>,
 }
 
 impl BotOutput {
+    /// Builds an output from its raw parts, used by executors that never spawn a real process.
+    #[must_use]
+    pub fn new(exit_code: i32, stdout: impl Into<String>, stderr: impl Into<String>) -> Self {
+        let stdout = stdout.into();
+        Self {
+            inner_buf: stdout.as_bytes().to_vec(),
+            stdout,
+            stderr: stderr.into(),
+            status: exit_status_from_code(exit_code),
+            ..Default::default()
+        }
+        .parsed()
+    }
+
     /// Returns the inner buffer of the output.
     #[must_use]
     pub fn inner_buf(&self) -> &[u8] {
@@ -32,6 +53,44 @@ impl BotOutput {
         &self.stderr
     }
 
+    /// Returns the exit status of the process.
+    #[must_use]
+    #[inline]
+    pub fn status(&self) -> ExitStatus {
+        self.status
+    }
+
+    /// The Blue Prism session `AutomateC` reported starting, if it printed one.
+    #[must_use]
+    #[inline]
+    pub fn session_id(&self) -> Option<&str> {
+        self.session_id.as_deref()
+    }
+
+    /// Why `AutomateC` failed, going by what it printed, `None` when it exited successfully.
+    #[must_use]
+    #[inline]
+    pub fn failure_reason(&self) -> Option<&FailureReason> {
+        self.failure.as_ref()
+    }
+
+    /// Reads the session ID, or the failure reason, from the captured output.
+    fn parsed(mut self) -> Self {
+        if self.status.success() {
+            self.session_id = parse_session_id(&self.stdout);
+        } else {
+            self.failure = Some(FailureReason::classify(&self.stdout, &self.stderr));
+        }
+        self
+    }
+
+    /// Returns true if the process exited successfully.
+    #[must_use]
+    #[inline]
+    pub fn success(&self) -> bool {
+        self.status.success()
+    }
+
     /// Adds a message to the output.
     #[allow(dead_code)]
     pub fn add_message<T>(&mut self, message: T)
@@ -54,10 +113,12 @@ impl From<Output> for BotOutput {
     fn from(output: Output) -> Self {
         Self {
             inner_buf: output.stdout.clone(),
-            stdout:    String::from_utf8_lossy(&output.stdout).to_string(),
-            stderr:    String::from_utf8_lossy(&output.stderr).to_string(),
-            status:    output.status,
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            status: output.status,
+            ..Default::default()
         }
+        .parsed()
     }
 }
 
@@ -67,6 +128,20 @@ impl From<ExitStatus> for BotOutput {
             status,
             ..Default::default()
         }
+        .parsed()
+    }
+}
+
+impl Serialize for BotOutput {
+    /// The exit code, captured output and what was read from it, without the raw buffer.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut output = serializer.serialize_struct("BotOutput", 5)?;
+        output.serialize_field("exit_code", &self.status.code())?;
+        output.serialize_field("session_id", &self.session_id)?;
+        output.serialize_field("failure_reason", &self.failure)?;
+        output.serialize_field("stdout", &self.stdout)?;
+        output.serialize_field("stderr", &self.stderr)?;
+        output.end()
     }
 }
 
@@ -79,6 +154,21 @@ impl Display for BotOutput {
     }
 }
 
+#[cfg(not(windows))]
+#[cfg(unix)]
+fn exit_status_from_code(exit_code: i32) -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    // Unix wait statuses keep the exit code in the second byte
+    ExitStatus::from_raw((exit_code & 0xff) << 8)
+}
+
+#[cfg(windows)]
+#[cfg(not(unix))]
+fn exit_status_from_code(exit_code: i32) -> ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    ExitStatus::from_raw(u32::from_ne_bytes(exit_code.to_ne_bytes()))
+}
+
 // impl From<crate::internals::Error> for BotOutput {
 //     fn from(error: crate::internals::Error) -> Self {
 //         Self {
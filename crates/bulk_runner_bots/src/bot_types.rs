@@ -1,20 +1,27 @@
+use serde::Serialize;
+
 use crate::base_bot::BaseBot;
 
-#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
 pub enum BotStatusReady {
     Idle,
     Pending,
+    #[serde(rename = "LOGGED OUT")]
     LoggedOut,
 }
 
-#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
 pub enum BotStatusNotReady {
     Offline,
     Unavailable,
     Private,
 }
 
-#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+/// Serialized as the status Blue Prism shows, e.g. "IDLE" or "LOGGED OUT".
+#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash, Serialize)]
+#[serde(into = "String")]
 pub enum BotStatus {
     Ready(BotStatusReady),
     NotReady(BotStatusNotReady),
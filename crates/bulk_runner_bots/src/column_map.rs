@@ -0,0 +1,100 @@
+use deadpool_tiberius::tiberius::Row;
+
+use crate::{Error, Result};
+
+/// Which result set columns a `BaseBot` is read from, matched by name (case-insensitively).
+///
+/// `extras` are optional, carried along on the `Bot` when the query returns them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnMap {
+    name:   String,
+    status: String,
+    extras: Vec<String>,
+}
+
+impl Default for ColumnMap {
+    #[inline]
+    fn default() -> Self {
+        ColumnMap::new("name", "status")
+    }
+}
+
+impl ColumnMap {
+    #[must_use]
+    pub fn new(name: impl Into<String>, status: impl Into<String>) -> Self {
+        ColumnMap {
+            name:   name.into(),
+            status: status.into(),
+            extras: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_extras<I, S>(mut self, extras: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.extras.extend(extras.into_iter().map(Into::into));
+        self
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn status(&self) -> &str {
+        &self.status
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn extras(&self) -> &[String] {
+        &self.extras
+    }
+
+    /// Reads the text in `column`, `Ok(None)` for a NULL.
+    ///
+    /// # Errors
+    /// Returns an error if the row has no such column, or it isn't a text column.
+    pub(crate) fn get<'a>(row: &'a Row, column: &str) -> Result<Option<&'a str>> {
+        let idx = Self::position(row, column).ok_or_else(|| {
+            Error::MissingColumn {
+                column:    column.to_string(),
+                available: row.columns().iter().map(|c| c.name().to_string()).collect(),
+            }
+        })?;
+
+        Self::get_text(row, idx, column)
+    }
+
+    /// Reads the text in an optional `column`, a missing column is treated like a NULL.
+    ///
+    /// # Errors
+    /// Returns an error if the column is there, but isn't a text column.
+    pub(crate) fn get_optional<'a>(row: &'a Row, column: &str) -> Result<Option<&'a str>> {
+        match Self::position(row, column) {
+            Some(idx) => Self::get_text(row, idx, column),
+            None => Ok(None),
+        }
+    }
+
+    fn get_text<'a>(row: &'a Row, idx: usize, column: &str) -> Result<Option<&'a str>> {
+        row.try_get::<&str, usize>(idx).map_err(|_| {
+            Error::MistypedColumn {
+                column: column.to_string(),
+                found:  row.columns()[idx].column_type(),
+            }
+        })
+    }
+
+    fn position(row: &Row, column: &str) -> Option<usize> {
+        row.columns()
+            .iter()
+            .position(|c| c.name().eq_ignore_ascii_case(column))
+    }
+}
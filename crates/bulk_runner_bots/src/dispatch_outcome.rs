@@ -0,0 +1,101 @@
+use std::process::Output;
+use std::time::Duration;
+
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+
+use crate::bot_output::BotOutput;
+
+/// The result of handing a single bot to a `DispatchExecutor`.
+#[derive(Debug)]
+pub enum DispatchOutcome {
+    /// `AutomateC` accepted the request and exited successfully.
+    Started(BotOutput),
+    /// `AutomateC` ran, but exited with a non-zero status.
+    Failed(BotOutput),
+    /// Nothing was spawned, the executor only recorded what it would have done.
+    Skipped,
+    /// `AutomateC` was still running when its time ran out, and was killed.
+    TimedOut(Duration),
+}
+
+impl DispatchOutcome {
+    #[must_use]
+    #[inline]
+    pub fn is_success(&self) -> bool {
+        matches!(self, DispatchOutcome::Started(_))
+    }
+
+    /// Returns the captured output, if a process was (or pretended to be) run.
+    #[must_use]
+    #[inline]
+    pub fn output(&self) -> Option<&BotOutput> {
+        match self {
+            DispatchOutcome::Started(output) | DispatchOutcome::Failed(output) => Some(output),
+            DispatchOutcome::Skipped | DispatchOutcome::TimedOut(_) => None,
+        }
+    }
+
+    /// Writes the outcome into `map`, for types serializing it alongside their own fields.
+    ///
+    /// That is its `kind` as "outcome", with the `BotOutput` fields when a process ran,
+    /// or the `timeout_ms` it was killed after.
+    ///
+    /// # Errors
+    /// Returns the serializer's error.
+    pub fn serialize_fields<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        map.serialize_entry("outcome", self.kind())?;
+        match self {
+            DispatchOutcome::Started(output) | DispatchOutcome::Failed(output) => {
+                map.serialize_entry("exit_code", &output.status().code())?;
+                map.serialize_entry("session_id", &output.session_id())?;
+                map.serialize_entry("failure_reason", &output.failure_reason())?;
+                map.serialize_entry("stdout", output.stdout())?;
+                map.serialize_entry("stderr", output.stderr())?;
+            }
+            DispatchOutcome::TimedOut(limit) => {
+                map.serialize_entry("timeout_ms", &u64::try_from(limit.as_millis()).unwrap_or(u64::MAX))?;
+            }
+            DispatchOutcome::Skipped => {}
+        }
+        Ok(())
+    }
+
+    /// The outcome's name in machine-readable output.
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DispatchOutcome::Started(_) => "STARTED",
+            DispatchOutcome::Failed(_) => "FAILED",
+            DispatchOutcome::Skipped => "SKIPPED",
+            DispatchOutcome::TimedOut(_) => "TIMED_OUT",
+        }
+    }
+}
+
+impl Serialize for DispatchOutcome {
+    /// `{"outcome": "STARTED", ...}`, see `serialize_fields`.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        self.serialize_fields(&mut map)?;
+        map.end()
+    }
+}
+
+impl From<BotOutput> for DispatchOutcome {
+    #[inline]
+    fn from(output: BotOutput) -> Self {
+        if output.success() {
+            DispatchOutcome::Started(output)
+        } else {
+            DispatchOutcome::Failed(output)
+        }
+    }
+}
+
+impl From<Output> for DispatchOutcome {
+    #[inline]
+    fn from(output: Output) -> Self {
+        BotOutput::from(output).into()
+    }
+}
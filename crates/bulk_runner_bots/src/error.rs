@@ -1,3 +1,4 @@
+use deadpool_tiberius::tiberius::ColumnType;
 use tokio::sync::oneshot::error::RecvError;
 
 #[derive(thiserror::Error, Debug)]
@@ -26,6 +27,30 @@ pub enum Error {
     #[error("Error parsing file: {0}")]
     Parse(#[from] std::string::FromUtf8Error),
 
-    #[error("Child process spawn failed")]
+    #[error("The query returned no '{column}' column, it returned: {}", .available.join(", "))]
+    MissingColumn {
+        column:    String,
+        available: Vec<String>,
+    },
+
+    #[error("The query returned the '{column}' column as {found:?}, it must be text (char, varchar, nchar or nvarchar)")]
+    MistypedColumn { column: String, found: ColumnType },
+
+    #[error("The '{0}' column was NULL or blank")]
+    NullColumn(String),
+
+    #[error("Could not spawn {}: {source}", .exe_path.display())]
+    Spawn {
+        exe_path: std::path::PathBuf,
+        source:   std::io::Error,
+    },
+
+    #[error("Could not wait on AutomateC for {resource}: {source}")]
+    Wait {
+        resource: String,
+        source:   std::io::Error,
+    },
+
+    #[error("The spawned child process was never handed back: {0}")]
     ChildProcessSpawnFailed(#[from] RecvError),
 }
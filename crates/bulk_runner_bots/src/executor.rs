@@ -0,0 +1,55 @@
+use std::path::{Path, PathBuf};
+
+use crate::base_bot::Bot;
+use crate::dispatch_outcome::DispatchOutcome;
+use crate::Result;
+
+/// Something that can take a bot, a process and a built `AutomateC` argument list
+/// and turn them into a `DispatchOutcome`.
+///
+/// The real implementation spawns `AutomateC`, the others exist so the
+/// query -> filter -> dispatch pipeline can be exercised without a Blue Prism install.
+#[async_trait::async_trait]
+pub trait DispatchExecutor: Send + Sync {
+    /// Runs (or pretends to run) `process` on `bot` using the given `AutomateC` arguments.
+    ///
+    /// # Errors
+    /// Returns an error if the executor could not produce an outcome at all,
+    /// a non-zero exit from `AutomateC` is reported as `DispatchOutcome::Failed` instead.
+    async fn execute(&self, bot: &Bot, process: &str, args: Vec<String>) -> Result<DispatchOutcome>;
+}
+
+/// Spawns the `AutomateC` executable for every dispatch.
+#[derive(Debug, Clone)]
+pub struct AutomateCExecutor {
+    exe_path: PathBuf,
+}
+
+impl AutomateCExecutor {
+    #[must_use]
+    pub fn new(exe_path: impl Into<PathBuf>) -> Self {
+        Self {
+            exe_path: exe_path.into(),
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn exe_path(&self) -> &Path {
+        &self.exe_path
+    }
+}
+
+impl Default for AutomateCExecutor {
+    /// Uses the `AutomateC` executable at `DEFAULT_EXE_PATH`.
+    fn default() -> Self {
+        Self::new(&*crate::DEFAULT_EXE_PATH)
+    }
+}
+
+#[async_trait::async_trait]
+impl DispatchExecutor for AutomateCExecutor {
+    async fn execute(&self, bot: &Bot, _process: &str, args: Vec<String>) -> Result<DispatchOutcome> {
+        crate::dispatch(&self.exe_path, bot.name.clone(), args).await
+    }
+}
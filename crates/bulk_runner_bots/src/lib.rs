@@ -1,20 +1,35 @@
+mod automatec_output;
 mod base_bot;
 mod base_bot_dispatch;
 mod bot_output;
 mod bot_types;
+mod column_map;
+mod dispatch_outcome;
 mod error;
+mod executor;
+mod redacted_args;
+// A fake executor for tests, kept out of release builds
+#[cfg(any(test, feature = "test-support"))]
+mod scripted_executor;
 
 use std::path::PathBuf;
 use std::sync::LazyLock;
 
 use tracing::{debug, error, info};
 
+pub use crate::automatec_output::{parse_session_id, FailureReason};
 pub use crate::base_bot::{BaseBot, Bot};
 pub use crate::base_bot_dispatch::dispatch;
 #[allow(unused_imports)]
 pub use crate::bot_output::BotOutput;
 pub use crate::bot_types::{BotStatus, BotStatusNotReady, BotStatusReady};
+pub use crate::column_map::ColumnMap;
+pub use crate::dispatch_outcome::DispatchOutcome;
 pub use crate::error::Error;
+pub use crate::executor::{AutomateCExecutor, DispatchExecutor};
+pub use crate::redacted_args::{RedactedArgs, REDACTED};
+#[cfg(any(test, feature = "test-support"))]
+pub use crate::scripted_executor::{ScriptedCall, ScriptedExecutor, ScriptedResponse};
 pub type Result<T> = std::result::Result<T, Error>;
 
 pub struct W<T>(pub T);
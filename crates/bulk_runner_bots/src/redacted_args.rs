@@ -0,0 +1,75 @@
+use std::fmt::{self, Debug, Display};
+
+/// Placeholder used in place of secret argument values whenever a command line is logged or reported.
+pub const REDACTED: &str = "********";
+
+/// Borrows an `AutomateC` argument list, formatting it with the value following `/password`,
+/// and the value of any password typed `/startp` input, replaced by `REDACTED`.
+///
+/// Every log line and report holding a command line goes through this, the arguments themselves are
+/// only ever handed to the spawned process.
+#[derive(Clone, Copy)]
+pub struct RedactedArgs<'a>(&'a [String]);
+
+impl<'a> RedactedArgs<'a> {
+    #[must_use]
+    #[inline]
+    pub fn new(args: &'a [String]) -> Self {
+        RedactedArgs(args)
+    }
+
+    /// Returns a copy of the arguments with their secrets redacted.
+    #[must_use]
+    pub fn to_vec(&self) -> Vec<String> {
+        let mut previous = "";
+        self.0
+            .iter()
+            .map(|arg| {
+                match std::mem::replace(&mut previous, arg) {
+                    switch if switch.eq_ignore_ascii_case("/password") => REDACTED.to_string(),
+                    switch if switch.eq_ignore_ascii_case("/startp") => redact_password_inputs(arg),
+                    _ => arg.clone(),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Display for RedactedArgs<'_> {
+    /// The arguments space separated, as they would be typed.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_vec().join(" "))
+    }
+}
+
+impl Debug for RedactedArgs<'_> {
+    /// The arguments as a list, like `Vec<String>`'s `Debug`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.to_vec()).finish()
+    }
+}
+
+/// Replaces the value of every `type="password"` input in `/startp` XML with `REDACTED`.
+fn redact_password_inputs(xml: &str) -> String {
+    let mut inputs = xml.split("<input ");
+    let mut redacted = inputs.next().unwrap_or_default().to_string();
+
+    for input in inputs {
+        redacted.push_str("<input ");
+        let value = input
+            .find("value=\"")
+            .map(|at| at + "value=\"".len())
+            .and_then(|start| input[start..].find('"').map(|len| (start, start + len)));
+
+        match value {
+            Some((start, end)) if input.contains("type=\"password\"") => {
+                redacted.push_str(&input[..start]);
+                redacted.push_str(REDACTED);
+                redacted.push_str(&input[end..]);
+            }
+            _ => redacted.push_str(input),
+        }
+    }
+
+    redacted
+}
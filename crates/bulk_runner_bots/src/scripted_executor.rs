@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::base_bot::Bot;
+use crate::bot_output::BotOutput;
+use crate::dispatch_outcome::DispatchOutcome;
+use crate::executor::DispatchExecutor;
+use crate::Result;
+
+/// The canned result a `ScriptedExecutor` hands back for a bot.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptedResponse {
+    exit_code: i32,
+    stdout:    String,
+    stderr:    String,
+    delay:     Duration,
+}
+
+impl ScriptedResponse {
+    #[must_use]
+    pub fn success() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn failure(exit_code: i32) -> Self {
+        Self {
+            exit_code,
+            ..Default::default()
+        }
+    }
+
+    #[must_use]
+    pub fn with_stdout(mut self, stdout: impl Into<String>) -> Self {
+        self.stdout = stdout.into();
+        self
+    }
+
+    #[must_use]
+    pub fn with_stderr(mut self, stderr: impl Into<String>) -> Self {
+        self.stderr = stderr.into();
+        self
+    }
+
+    /// How long the executor should wait before answering, to mimic a slow `AutomateC`.
+    #[must_use]
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+/// A single invocation recorded by a `ScriptedExecutor`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptedCall {
+    pub bot_name: String,
+    pub process:  String,
+    pub args:     Vec<String>,
+}
+
+/// A fake executor that answers with pre-configured responses per bot name,
+/// and records every call it receives.
+#[derive(Debug, Default)]
+pub struct ScriptedExecutor {
+    responses: HashMap<String, ScriptedResponse>,
+    fallback:  ScriptedResponse,
+    calls:     Mutex<Vec<ScriptedCall>>,
+}
+
+impl ScriptedExecutor {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the response for a single bot, matched case-insensitively on its name.
+    #[must_use]
+    pub fn with_response(mut self, bot_name: impl AsRef<str>, response: ScriptedResponse) -> Self {
+        self.responses.insert(bot_name.as_ref().to_uppercase(), response);
+        self
+    }
+
+    /// Sets the response used for any bot without its own entry.
+    #[must_use]
+    pub fn with_fallback(mut self, response: ScriptedResponse) -> Self {
+        self.fallback = response;
+        self
+    }
+
+    /// Returns every call received so far, in the order they arrived.
+    ///
+    /// # Panics
+    /// Will panic if a previous call panicked while holding the call log.
+    #[must_use]
+    pub fn calls(&self) -> Vec<ScriptedCall> {
+        self.calls.lock().expect("Scripted call log poisoned").clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl DispatchExecutor for ScriptedExecutor {
+    async fn execute(&self, bot: &Bot, process: &str, args: Vec<String>) -> Result<DispatchOutcome> {
+        self.calls
+            .lock()
+            .map_err(|e| crate::Error::Generic(e.to_string()))?
+            .push(ScriptedCall {
+                bot_name: bot.name.clone(),
+                process: process.to_string(),
+                args,
+            });
+
+        let response = self
+            .responses
+            .get(&bot.name.to_uppercase())
+            .unwrap_or(&self.fallback);
+
+        if !response.delay.is_zero() {
+            tokio::time::sleep(response.delay).await;
+        }
+
+        Ok(BotOutput::new(response.exit_code, response.stdout.as_str(), response.stderr.as_str()).into())
+    }
+}
@@ -0,0 +1,51 @@
+use std::fmt::{self, Debug};
+
+use bulk_runner_bots::REDACTED;
+
+use crate::command_builder::AutomateBuilderBase;
+
+/// How `AutomateC` signs in to Blue Prism for each dispatch.
+#[derive(Clone, Default)]
+pub enum AutomateAuth {
+    /// `/sso`, as the Windows user the bulk runner runs as.
+    #[default]
+    Sso,
+    /// `/user` and `/password`, for service accounts and hosts that can't use single sign-on.
+    Credentials { user: String, password: String },
+}
+
+impl AutomateAuth {
+    #[must_use]
+    pub fn credentials(user: impl Into<String>, password: impl Into<String>) -> Self {
+        AutomateAuth::Credentials {
+            user:     user.into(),
+            password: password.into(),
+        }
+    }
+
+    /// Adds the switches signing in to the builder.
+    pub fn apply(&self, builder: &mut AutomateBuilderBase) {
+        match self {
+            AutomateAuth::Sso => {
+                builder.with_sso();
+            }
+            AutomateAuth::Credentials { user, password } => {
+                builder.with_user(user).with_password(password);
+            }
+        }
+    }
+}
+
+impl Debug for AutomateAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AutomateAuth::Sso => f.write_str("Sso"),
+            AutomateAuth::Credentials { user, .. } => {
+                f.debug_struct("Credentials")
+                    .field("user", user)
+                    .field("password", &REDACTED)
+                    .finish()
+            }
+        }
+    }
+}
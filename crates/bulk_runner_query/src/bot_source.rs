@@ -0,0 +1,307 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use bulk_runner_bots::{BaseBot, Bot, ColumnMap};
+use serde_json::Value;
+
+use crate::db_info::DbInfo;
+use crate::query_engine::QueryEngine;
+use crate::run_limit::RunLimit;
+use crate::sql_validation::SqlValidation;
+use crate::{warn, Error, Result};
+
+/// Where the bots for a run come from.
+///
+/// Sources hand over every bot they read, the runner filters and limits them the same way for all of them.
+#[async_trait::async_trait]
+pub trait BotSource: Send + Sync {
+    /// Hands each bot to `on_bot` as it is read, returning how many records were read.
+    ///
+    /// `limit` is the run's limit, which the SQL file may apply with `TOP (@P1)`.
+    /// Only the bots that are dispatched count toward it, so sources hand over every bot they read
+    /// and leave counting it to the runner.
+    async fn stream_bots(&self, limit: RunLimit, on_bot: &mut (dyn FnMut(BaseBot) + Send)) -> Result<usize>;
+
+    /// What the source is, for logging.
+    fn describe(&self) -> String;
+}
+
+/// Bots queried from the Blue Prism database with the SQL file.
+pub struct SqlBotSource {
+    db_info: DbInfo,
+    sql:     String,
+    columns: ColumnMap,
+}
+
+impl SqlBotSource {
+    #[must_use]
+    pub fn new(db_info: DbInfo, sql: impl Into<String>, columns: ColumnMap) -> Self {
+        SqlBotSource {
+            db_info,
+            sql: sql.into(),
+            columns,
+        }
+    }
+
+    /// Checks the SQL returns the columns the bots are read from, without reading any rows.
+    ///
+    /// # Errors
+    /// Returns an error if the database can't be reached, or the SQL doesn't compile.
+    pub async fn validate(&self) -> Result<SqlValidation> {
+        let returned = QueryEngine::new(self.db_info.clone())?
+            .result_columns(&self.sql)
+            .await?;
+        Ok(SqlValidation::new(returned, &self.columns))
+    }
+}
+
+#[async_trait::async_trait]
+impl BotSource for SqlBotSource {
+    async fn stream_bots(&self, limit: RunLimit, on_bot: &mut (dyn FnMut(BaseBot) + Send)) -> Result<usize> {
+        QueryEngine::new(self.db_info.clone())?
+            .stream_bots(&self.sql, limit, &self.columns, on_bot)
+            .await
+    }
+
+    fn describe(&self) -> String {
+        format!("database {} on {}", self.db_info.db, self.db_info.host)
+    }
+}
+
+/// A fixed list of bots held in memory.
+#[derive(Clone, Debug, Default)]
+pub struct StaticBotSource {
+    bots: Vec<BaseBot>,
+}
+
+impl StaticBotSource {
+    #[must_use]
+    pub fn new(bots: Vec<BaseBot>) -> Self {
+        StaticBotSource { bots }
+    }
+}
+
+impl From<Vec<Bot>> for StaticBotSource {
+    fn from(bots: Vec<Bot>) -> Self {
+        StaticBotSource::new(bots.into_iter().map(BaseBot::from).collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl BotSource for StaticBotSource {
+    async fn stream_bots(&self, _limit: RunLimit, on_bot: &mut (dyn FnMut(BaseBot) + Send)) -> Result<usize> {
+        self.bots.iter().cloned().for_each(on_bot);
+        Ok(self.bots.len())
+    }
+
+    fn describe(&self) -> String {
+        format!("{} given bot(s)", self.bots.len())
+    }
+}
+
+/// Only the named resources, with their live status read from another source.
+///
+/// Names are matched case-insensitively. The inner source is read without a limit,
+/// and every record it read is counted, named or not.
+pub struct NamedBotSource {
+    inner: Arc<dyn BotSource>,
+    names: BTreeSet<String>,
+}
+
+impl NamedBotSource {
+    #[must_use]
+    pub fn new(inner: Arc<dyn BotSource>, names: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        NamedBotSource {
+            inner,
+            names: names
+                .into_iter()
+                .map(|name| name.as_ref().to_uppercase())
+                .collect(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl BotSource for NamedBotSource {
+    async fn stream_bots(&self, _limit: RunLimit, on_bot: &mut (dyn FnMut(BaseBot) + Send)) -> Result<usize> {
+        let mut found = BTreeSet::new();
+        let read = self
+            .inner
+            .stream_bots(RunLimit::Unlimited, &mut |base_bot| {
+                let Some(name) = base_bot.name().map(str::to_uppercase) else {
+                    return;
+                };
+                if self.names.contains(&name) && found.insert(name) {
+                    on_bot(base_bot);
+                }
+            })
+            .await?;
+
+        for missing in self.names.difference(&found) {
+            warn!(
+                "->> {:<12} - {} was not returned by the {}",
+                "SOURCE:: Unknown resource",
+                missing,
+                self.inner.describe()
+            );
+        }
+
+        Ok(read)
+    }
+
+    fn describe(&self) -> String {
+        format!("{} named resource(s) from the {}", self.names.len(), self.inner.describe())
+    }
+}
+
+/// An exported resource list, a `.csv` file with a header row or a `.json` array of objects.
+///
+/// Fields are found by the `ColumnMap` names, case-insensitively, like the columns of a query.
+pub struct FileBotSource {
+    path:    PathBuf,
+    columns: ColumnMap,
+}
+
+impl FileBotSource {
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>, columns: ColumnMap) -> Self {
+        FileBotSource {
+            path: path.into(),
+            columns,
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn invalid(&self, reason: impl Into<String>) -> Error {
+        Error::InvalidBotsFile(self.path.clone(), reason.into())
+    }
+
+    fn read_csv(&self, contents: &str, on_bot: &mut (dyn FnMut(BaseBot) + Send)) -> Result<usize> {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(contents.as_bytes());
+
+        let headers = reader.headers().map_err(|e| self.invalid(e.to_string()))?.clone();
+        let position = |column: &str| headers.iter().position(|h| h.eq_ignore_ascii_case(column));
+        let missing = |column: &str| {
+            Error::from(bulk_runner_bots::Error::MissingColumn {
+                column:    column.to_string(),
+                available: headers.iter().map(str::to_string).collect(),
+            })
+        };
+
+        let name_at = position(self.columns.name()).ok_or_else(|| missing(self.columns.name()))?;
+        let status_at = position(self.columns.status()).ok_or_else(|| missing(self.columns.status()))?;
+        let extras_at = self
+            .columns
+            .extras()
+            .iter()
+            .filter_map(|extra| position(extra).map(|at| (extra, at)))
+            .collect::<Vec<_>>();
+
+        let mut read = 0;
+        for record in reader.records() {
+            let record = record.map_err(|e| self.invalid(e.to_string()))?;
+            read += 1;
+
+            let field = |at: usize| record.get(at).filter(|value| !value.is_empty());
+            let Some(name) = field(name_at) else {
+                warn!(
+                    "->> {:<12} - record {}: '{}' is blank",
+                    "FILE:: Rejected row",
+                    read,
+                    self.columns.name()
+                );
+                continue;
+            };
+
+            let mut base_bot = BaseBot::new(name, field(status_at).map(str::to_string));
+            for (extra, at) in &extras_at {
+                if let Some(value) = field(*at) {
+                    base_bot = base_bot.with_extra(extra.as_str(), value);
+                }
+            }
+            on_bot(base_bot);
+        }
+
+        Ok(read)
+    }
+
+    fn read_json(&self, contents: &str, on_bot: &mut (dyn FnMut(BaseBot) + Send)) -> Result<usize> {
+        let Value::Array(records) =
+            serde_json::from_str(contents).map_err(|e| self.invalid(e.to_string()))?
+        else {
+            return Err(self.invalid("expected an array of objects"));
+        };
+
+        let mut read = 0;
+        for record in records {
+            read += 1;
+
+            let Value::Object(fields) = record else {
+                warn!("->> {:<12} - record {}: not an object", "FILE:: Rejected row", read);
+                continue;
+            };
+            let field = |column: &str| {
+                fields
+                    .iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case(column))
+                    .map(|(_, value)| value)
+            };
+            let text = |column: &str| {
+                match field(column) {
+                    Some(Value::String(value)) if !value.trim().is_empty() => Ok(Some(value.trim())),
+                    Some(Value::String(_) | Value::Null) | None => Ok(None),
+                    Some(_) => Err(format!("'{column}' is not text")),
+                }
+            };
+
+            let bot = text(self.columns.name()).and_then(|name| {
+                let name = name.ok_or_else(|| format!("'{}' is missing or blank", self.columns.name()))?;
+                let mut base_bot = BaseBot::new(name, text(self.columns.status())?.map(str::to_string));
+                for extra in self.columns.extras() {
+                    if let Some(value) = text(extra)? {
+                        base_bot = base_bot.with_extra(extra.as_str(), value);
+                    }
+                }
+                Ok(base_bot)
+            });
+
+            match bot {
+                Ok(base_bot) => on_bot(base_bot),
+                Err(reason) => warn!("->> {:<12} - record {}: {}", "FILE:: Rejected row", read, reason),
+            }
+        }
+
+        Ok(read)
+    }
+}
+
+#[async_trait::async_trait]
+impl BotSource for FileBotSource {
+    async fn stream_bots(&self, _limit: RunLimit, on_bot: &mut (dyn FnMut(BaseBot) + Send)) -> Result<usize> {
+        let contents = tokio::fs::read_to_string(&self.path).await?;
+
+        let extension = self
+            .path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_lowercase);
+
+        match extension.as_deref() {
+            Some("csv") => self.read_csv(&contents, on_bot),
+            Some("json") => self.read_json(&contents, on_bot),
+            _ => Err(self.invalid("expected a .csv or .json file")),
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("file {}", self.path.display())
+    }
+}
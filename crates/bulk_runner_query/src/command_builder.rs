@@ -1,3 +1,9 @@
+use std::fmt::{self, Debug, Display, Write};
+
+use bulk_runner_bots::RedactedArgs;
+
+use crate::{Error, Result};
+
 #[inline]
 fn handle_quoted(arg: &str) -> std::borrow::Cow<'_, str> {
     if arg.contains(' ') || arg.contains('"') {
@@ -12,6 +18,38 @@ pub struct AutomateCCommander {
     pub args_vec: Vec<String>,
 }
 
+impl AutomateCCommander {
+    /// Returns a copy of the arguments with the value following `/password`,
+    /// and the value of any password typed `/startp` input, replaced by `REDACTED`.
+    #[must_use]
+    pub fn redacted(&self) -> Vec<String> {
+        self.redacted_args().to_vec()
+    }
+
+    /// Borrows the arguments for logging, formatting them with their secrets redacted.
+    #[must_use]
+    #[inline]
+    pub fn redacted_args(&self) -> RedactedArgs<'_> {
+        RedactedArgs::new(&self.args_vec)
+    }
+}
+
+impl Display for AutomateCCommander {
+    /// The arguments space separated, with their secrets redacted.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.redacted_args(), f)
+    }
+}
+
+impl Debug for AutomateCCommander {
+    /// Never shows the secrets `args_vec` holds.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AutomateCCommander")
+            .field("args_vec", &self.redacted_args())
+            .finish()
+    }
+}
+
 impl From<AutomateCCommander> for Vec<String> {
     #[inline]
     fn from(cmd: AutomateCCommander) -> Self {
@@ -20,15 +58,141 @@ impl From<AutomateCCommander> for Vec<String> {
 }
 
 impl From<AutomateBuilderBase> for AutomateCCommander {
+    /// Builds the arguments as `build` does, `/startp` and its inputs included.
     #[inline]
     fn from(cmd: AutomateBuilderBase) -> Self {
-        AutomateCCommander { args_vec: cmd.args }
+        cmd.build()
+    }
+}
+
+/// A switch `AutomateC` understands, recorded as the builder adds it so `try_build` can check the combination.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Switch {
+    Sso,
+    Run,
+    StartParams,
+    Resource,
+    Port,
+    User,
+    Password,
+    DbConName,
+    Status,
+    GetLog,
+    Stop,
+    ListQueues,
+    QueueItems,
+}
+
+impl Switch {
+    /// Switches naming what `AutomateC` is being asked to do, exactly one of them is allowed.
+    const ACTIONS: [Switch; 6] = [
+        Switch::Run,
+        Switch::Status,
+        Switch::GetLog,
+        Switch::Stop,
+        Switch::ListQueues,
+        Switch::QueueItems,
+    ];
+    /// Switches that only make sense alongside `/run`.
+    const RUN_ONLY: [Switch; 3] = [Switch::StartParams, Switch::Resource, Switch::Port];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Switch::Sso => "/sso",
+            Switch::Run => "/run",
+            Switch::StartParams => "/startp",
+            Switch::Resource => "/resource",
+            Switch::Port => "/port",
+            Switch::User => "/user",
+            Switch::Password => "/password",
+            Switch::DbConName => "/dbconname",
+            Switch::Status => "/status",
+            Switch::GetLog => "/getlog",
+            Switch::Stop => "/stop",
+            Switch::ListQueues => "/listqueues",
+            Switch::QueueItems => "/queueitems",
+        }
+    }
+}
+
+/// The data type of a process input passed with `/startp`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputType {
+    Text,
+    Number,
+    Flag,
+    Date,
+    DateTime,
+    Time,
+    TimeSpan,
+    Password,
+}
+
+impl InputType {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            InputType::Text => "text",
+            InputType::Number => "number",
+            InputType::Flag => "flag",
+            InputType::Date => "date",
+            InputType::DateTime => "datetime",
+            InputType::Time => "time",
+            InputType::TimeSpan => "timespan",
+            InputType::Password => "password",
+        }
     }
 }
 
+/// A named startup parameter handed to the process being run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProcessInput {
+    pub name:       String,
+    pub input_type: InputType,
+    pub value:      String,
+}
+
+impl ProcessInput {
+    #[must_use]
+    pub fn new(name: impl Into<String>, input_type: InputType, value: impl Into<String>) -> Self {
+        ProcessInput {
+            name: name.into(),
+            input_type,
+            value: value.into(),
+        }
+    }
+}
+
+/// Renders the inputs as the `<inputs>` XML `/startp` expects.
+fn inputs_xml(inputs: &[ProcessInput]) -> String {
+    let escape = |value: &str| {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&apos;")
+    };
+
+    let mut xml = String::from("<inputs>");
+    for input in inputs {
+        let _ = write!(
+            xml,
+            "<input name=\"{}\" type=\"{}\" value=\"{}\" />",
+            escape(&input.name),
+            input.input_type.as_str(),
+            escape(&input.value)
+        );
+    }
+    xml.push_str("</inputs>");
+    xml
+}
+
 #[derive(Debug, Default)]
 pub struct AutomateBuilderBase {
-    args: Vec<String>,
+    args:     Vec<String>,
+    switches: Vec<Switch>,
+    inputs:   Vec<ProcessInput>,
 }
 
 impl AutomateBuilderBase {
@@ -74,60 +238,183 @@ impl AutomateBuilderBase {
         self
     }
 
+    /// Adds a startup parameter for the process, all of them are passed with a single `/startp`.
+    #[inline]
+    pub fn with_input(&mut self, input: ProcessInput) -> &mut Self {
+        if self.inputs.is_empty() {
+            self.switches.push(Switch::StartParams);
+        }
+        self.inputs.push(input);
+        self
+    }
+
+    /// The port the resource listens on, when it isn't the default 8181.
+    #[inline]
+    pub fn with_port(&mut self, port: u16) -> &mut Self {
+        self.switch_with(Switch::Port, port.to_string())
+    }
+
+    /// The named database connection, from the Blue Prism client's connection list, to run against.
+    #[inline]
+    pub fn with_dbconname(&mut self, connection: impl AsRef<str>) -> &mut Self {
+        self.switch_with(Switch::DbConName, connection.as_ref())
+    }
+
+    /// Asks for the status of a session, rather than running a process.
+    #[inline]
+    pub fn with_status(&mut self, session_id: impl AsRef<str>) -> &mut Self {
+        self.switch_with(Switch::Status, session_id.as_ref())
+    }
+
+    /// Asks for the log of a session, rather than running a process.
+    #[inline]
+    pub fn with_getlog(&mut self, session_id: impl AsRef<str>) -> &mut Self {
+        self.switch_with(Switch::GetLog, session_id.as_ref())
+    }
+
+    /// Asks for a running session to be stopped, rather than running a process.
+    #[inline]
+    pub fn with_stop(&mut self, session_id: impl AsRef<str>) -> &mut Self {
+        self.switch_with(Switch::Stop, session_id.as_ref())
+    }
+
+    /// Lists the work queues, rather than running a process.
+    #[inline]
+    pub fn with_list_queues(&mut self) -> &mut Self {
+        self.switch(Switch::ListQueues)
+    }
+
+    /// Lists the items in a work queue, rather than running a process.
+    #[inline]
+    pub fn with_queue_items(&mut self, queue: impl AsRef<str>) -> &mut Self {
+        self.switch_with(Switch::QueueItems, queue.as_ref())
+    }
+
+    /// Builds the arguments as given, without checking they make sense together.
     #[must_use]
     #[inline]
     pub fn build(&self) -> AutomateCCommander {
-        AutomateCCommander {
-            args_vec: self.args.clone(),
+        let mut args_vec = self.args.clone();
+        if !self.inputs.is_empty() {
+            args_vec.push(Switch::StartParams.as_str().to_string());
+            args_vec.push(inputs_xml(&self.inputs));
+        }
+
+        AutomateCCommander { args_vec }
+    }
+
+    /// Builds the arguments, once they are known to make a command `AutomateC` would accept.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidAutomateCommand` if a switch is repeated, there isn't exactly one action
+    /// (`/run`, `/status`, `/getlog`, `/stop`, `/listqueues` or `/queueitems`), `/startp`, `/resource` or `/port`
+    /// are given without `/run`, or the credentials aren't either `/sso` or both `/user` and `/password`.
+    pub fn try_build(&self) -> Result<AutomateCCommander> {
+        let invalid = |reason: String| Err(Error::InvalidAutomateCommand(reason));
+        let has = |switch: Switch| self.switches.contains(&switch);
+
+        if let Some(switch) = self
+            .switches
+            .iter()
+            .enumerate()
+            .find_map(|(at, switch)| self.switches[..at].contains(switch).then_some(switch))
+        {
+            return invalid(format!("{} is given more than once", switch.as_str()));
+        }
+
+        let actions = Switch::ACTIONS
+            .into_iter()
+            .filter(|action| has(*action))
+            .map(Switch::as_str)
+            .collect::<Vec<_>>();
+        match actions.as_slice() {
+            [] => {
+                return invalid(
+                    "no action given, expected /run, /status, /getlog, /stop, /listqueues or /queueitems"
+                        .to_string(),
+                )
+            }
+            [_] => {}
+            actions => {
+                return invalid(format!("only one action can be given, found {}", actions.join(" and ")))
+            }
+        }
+
+        if !has(Switch::Run) {
+            if let Some(switch) = Switch::RUN_ONLY.into_iter().find(|switch| has(*switch)) {
+                return invalid(format!("{} can only be used with /run", switch.as_str()));
+            }
+        }
+
+        match (has(Switch::Sso), has(Switch::User), has(Switch::Password)) {
+            (true, false, false) | (false, true, true) => Ok(self.build()),
+            (true, _, _) => invalid("/sso cannot be combined with /user or /password".to_string()),
+            (false, true, false) => invalid("/user needs a /password".to_string()),
+            (false, false, true) => invalid("/password needs a /user".to_string()),
+            (false, false, false) => {
+                invalid("no credentials given, use /sso or /user and /password".to_string())
+            }
         }
     }
 }
 
 impl From<AutomateBuilderBase> for String {
+    /// The arguments `build` gives, space separated.
     #[inline]
     fn from(cmd: AutomateBuilderBase) -> Self {
-        cmd.args.join(" ")
+        cmd.build().args_vec.join(" ")
     }
 }
 
 impl AutomateBuilderBase {
+    /// Internal function to add a switch to the args, and record it for `try_build`.
+    #[inline]
+    fn switch(&mut self, switch: Switch) -> &mut Self {
+        self.switches.push(switch);
+        self.args.push(switch.as_str().into());
+        self
+    }
+
+    /// Internal function to add a switch and the value following it to the args.
+    #[inline]
+    fn switch_with(&mut self, switch: Switch, value: impl Into<String>) -> &mut Self {
+        self.switch(switch);
+        self.args.push(value.into());
+        self
+    }
+
     /// Internal function to add the /sso argument to the args.
     /// This is used for calling the `AutomateC` executable with the /sso flag, the public method is `with_sso`.
     #[inline]
     fn sso(&mut self) -> &mut Self {
-        self.args.push("/sso".into());
-        self
+        self.switch(Switch::Sso)
     }
 
     /// Internal function to add the /run argument to the args.
     /// This is used for calling the `AutomateC` executable with the /run flag, the public method is `with_process`.
     #[inline]
     fn run(&mut self) -> &mut Self {
-        self.args.push("/run".into());
-        self
+        self.switch(Switch::Run)
     }
 
     /// Internal function to add the /resource argument to the args.
     /// This is used for calling the `AutomateC` executable with the /resource flag, the public method is `with_resource`.
     #[inline]
     fn resource(&mut self) -> &mut Self {
-        self.args.push("/resource".into());
-        self
+        self.switch(Switch::Resource)
     }
 
     /// Internal function to add the /user argument to the args.
     /// This is used for calling the `AutomateC` executable with the /user flag, the public method is `with_user`.
     #[inline]
     fn user(&mut self) -> &mut Self {
-        self.args.push("/user".into());
-        self
+        self.switch(Switch::User)
     }
 
     /// Internal function to add the /password argument to the args.
     /// This is used for calling the `AutomateC` executable with the /password flag, the public method is `with_password`.
     #[inline]
     fn password(&mut self) -> &mut Self {
-        self.args.push("/password".into());
-        self
+        self.switch(Switch::Password)
     }
 }
@@ -1,16 +1,45 @@
-#[cfg(not(windows))]
-#[cfg(unix)]
 use std::env::VarError;
 
 use deadpool_tiberius::tiberius::AuthMethod;
 
+#[derive(Clone, Debug)]
 pub struct DbInfo {
-    pub host: String,
-    pub auth: AuthMethod,
-    pub db:   String,
+    pub host:     String,
+    pub auth:     AuthMethod,
+    pub db:       String,
+    /// Explicit TCP port, when not set the SQL Server default of 1433 is used
+    /// (or the SQL Browser is asked, if an instance is set).
+    pub port:     Option<u16>,
+    /// Named SQL Server instance, resolved through the SQL Browser service.
+    pub instance: Option<String>,
 }
 
 impl DbInfo {
+    #[must_use]
+    pub fn new(host: impl Into<String>, db: impl Into<String>, auth: AuthMethod) -> Self {
+        Self {
+            host: host.into(),
+            auth,
+            db: db.into(),
+            port: None,
+            instance: None,
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
     /// Retrieves the database information from environment variables.
     ///
     /// # Note
@@ -23,10 +52,8 @@ impl DbInfo {
     #[cfg(not(windows))]
     #[cfg(unix)]
     pub fn auth_from_env() -> Result<Self, VarError> {
-        let host = crate::PROD_HOST.into();
-        let db = crate::PROD_DB.into();
         let auth = sql_auth_method_from_env()?;
-        Ok(Self { host, auth, db })
+        Ok(Self::new(crate::PROD_HOST, crate::PROD_DB, auth))
     }
 }
 
@@ -39,11 +66,7 @@ impl From<String> for DbInfo {
         let mut split = db_info.split_whitespace();
         let host = split.next().unwrap().to_string();
         let db = split.next().unwrap().to_string();
-        Self {
-            host,
-            auth: AuthMethod::Integrated,
-            db,
-        }
+        Self::new(host, db, AuthMethod::Integrated)
     }
 }
 
@@ -51,11 +74,7 @@ impl From<String> for DbInfo {
 #[cfg(not(unix))]
 impl Default for DbInfo {
     fn default() -> Self {
-        Self {
-            host: crate::PROD_HOST.into(),
-            auth: AuthMethod::Integrated,
-            db:   crate::PROD_DB.into(),
-        }
+        Self::new(crate::PROD_HOST, crate::PROD_DB, AuthMethod::Integrated)
     }
 }
 
@@ -68,7 +87,7 @@ impl From<String> for DbInfo {
         let db = split.next().unwrap().to_string();
 
         let auth = sql_auth_method_from_env().expect("Failed to get SQL auth from env");
-        Self { host, auth, db }
+        Self::new(host, db, auth)
     }
 }
 
@@ -97,8 +116,6 @@ impl Default for DbInfo {
 ///
 /// # Errors
 /// Will return an error if either environment variable is not set.
-#[cfg(not(windows))]
-#[cfg(unix)]
 pub fn sql_auth_method_from_env() -> Result<AuthMethod, VarError> {
     let user = sql_user_from_env()?;
     let password = sql_password_from_env()?;
@@ -112,8 +129,6 @@ pub fn sql_auth_method_from_env() -> Result<AuthMethod, VarError> {
 ///
 /// # Errors
 /// Returns an error if the environment variable is not set.
-#[cfg(not(windows))]
-#[cfg(unix)]
 pub fn sql_user_from_env() -> Result<String, VarError> {
     std::env::var("PROD_SQL_USER")
 }
@@ -125,8 +140,6 @@ pub fn sql_user_from_env() -> Result<String, VarError> {
 ///
 /// # Errors
 /// Returns an error if the environment variable is not set.
-#[cfg(not(windows))]
-#[cfg(unix)]
 pub fn sql_password_from_env() -> Result<String, VarError> {
     std::env::var("PROD_SQL_PASSWORD")
 }
@@ -1,108 +1,327 @@
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use bulk_runner_bots::{BaseBot, Bot};
-use tokio::sync::mpsc::UnboundedSender;
+use bulk_runner_bots::{Bot, DispatchExecutor, DispatchOutcome};
+use chrono::Local;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::task::{JoinError, JoinSet};
 
-use crate::query_engine::QueryEngine;
-use crate::{error, info, Result};
+use crate::automate_auth::AutomateAuth;
+use crate::bot_source::BotSource;
+use crate::command_builder::{AutomateBuilderBase, AutomateCCommander};
+use crate::dispatch_timeouts::DispatchTimeouts;
+use crate::report::{DispatchAttempt, DispatchRecord, DispatchReport};
+use crate::retry_policy::RetryPolicy;
+use crate::run_limit::RunLimit;
+use crate::{error, info, warn, Error, Result};
 
+/// Reads the bots from `source` and sends each down `tx` as soon as it is read,
+/// so dispatch can start before the source has been fully read.
+///
+/// Every bot is sent, available or not, the receiver counts `limit_total_runnable` over the bots it dispatches
+/// once they have been filtered. The limit is handed to the source, which binds it to the SQL file's `@P1`
+/// and warns when it can't be honoured.
+///
+/// # Errors
+/// Returns an error if the source could not be read, bots already sent before the failure stay sent.
 pub async fn query_database(
     tx: UnboundedSender<Bot>,
-    parsed_sql_file: impl AsRef<str>,
-    limit_total_runnable: usize,
-) {
-    let mut base_bots: Vec<BaseBot> = match QueryEngine::default()
-        .get_bots(parsed_sql_file.as_ref(), limit_total_runnable)
-        .await
-    {
-        Ok(bots) => bots,
+    source: Arc<dyn BotSource>,
+    limit_total_runnable: RunLimit,
+) -> Result<()> {
+    info!("->> {:<12} - {}", "QUERY:: Reading bots from", source.describe());
+
+    let streamed = source
+        .stream_bots(limit_total_runnable, &mut |base_bot| {
+            let filled_bot: Bot = Bot::from(base_bot);
+            if filled_bot.is_available().is_none() {
+                info!("{:<12} - {:?}", "QUERY:: Not available bot", filled_bot.status);
+            } else {
+                info!("{:<12} - {:?}", "QUERY:: Ready bot", filled_bot.status);
+            }
+            tx.send(filled_bot).unwrap_or_default();
+        })
+        .await;
+    drop(tx);
+
+    match streamed {
+        Ok(rows) => {
+            info!("->> {:<12} - {}", "QUERY:: Rows streamed", rows);
+            Ok(())
+        }
         Err(e) => {
             error!("->> {:<12} - {:?}", "QUERY:: ERROR", e);
-            vec![]
+            Err(e)
+        }
+    }
+}
+
+/// Dispatches every `(Bot, process)` pair through the given executor,
+/// with at most `total_bots` dispatches in flight at once.
+///
+/// Returns a `DispatchReport` holding one record per bot.
+pub async fn cli_dispatch(
+    dispatch_bots: Vec<(Bot, String)>,
+    total_bots: usize,
+    executor: Arc<dyn DispatchExecutor>,
+) -> DispatchReport {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    for packet in dispatch_bots {
+        tx.send(packet).unwrap_or_default();
+    }
+    drop(tx);
+
+    stream_dispatch(
+        rx,
+        total_bots,
+        executor,
+        DispatchTimeouts::unbounded(),
+        RetryPolicy::default(),
+        AutomateAuth::default(),
+    )
+    .await
+}
+
+/// Dispatches every `(Bot, process)` pair received on `rx` the moment it arrives,
+/// with at most `concurrency_limit` dispatches in flight at once.
+///
+/// A dispatch running past its `timeouts` is killed and recorded as timed out, freeing its slot.
+/// Failures the `retry` policy allows are tried again after a backoff, without holding a slot while waiting.
+/// Every `AutomateC` invocation signs in with `auth`.
+/// Once the deadline passes no more pairs are taken from `rx`, and those already waiting are
+/// recorded as never dispatched.
+///
+/// Returns once `rx` is closed and every dispatch has finished, with one record per bot.
+pub async fn stream_dispatch(
+    mut rx: UnboundedReceiver<(Bot, String)>,
+    concurrency_limit: usize,
+    executor: Arc<dyn DispatchExecutor>,
+    timeouts: DispatchTimeouts,
+    retry: RetryPolicy,
+    auth: AutomateAuth,
+) -> DispatchReport {
+    let retry = Arc::new(retry);
+    let auth = Arc::new(auth);
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrency_limit));
+    let mut in_flight = JoinSet::new();
+    let mut records = Vec::new();
+
+    let deadline = async {
+        match timeouts.deadline {
+            Some(deadline) => tokio::time::sleep_until(deadline).await,
+            None => std::future::pending().await,
         }
     };
+    tokio::pin!(deadline);
+    let mut deadline_passed = false;
+
+    loop {
+        tokio::select! {
+            received = rx.recv() => {
+                let Some((bot, process_name)) = received else {
+                    break;
+                };
 
-    // TODO: STREAM: We can stream these instead of iterating over them?
-    while let Some(base_bot) = base_bots.pop() {
-        let filled_bot: Bot = Bot::from(base_bot);
-        match filled_bot.status {
-            bulk_runner_bots::BotStatus::Ready(ref status) => {
-                info!("{:<12} - {:?}", "QUERY:: Ready bot", status);
-                tx.send(filled_bot).unwrap_or_default();
+                let semaphore = semaphore.clone();
+                let executor = executor.clone();
+                let retry = retry.clone();
+                let auth = auth.clone();
+                in_flight.spawn(async move {
+                    dispatch_one(&bot, &process_name, semaphore.as_ref(), executor.as_ref(), timeouts, &retry, &auth)
+                        .await
+                });
+            }
+            // Stop taking new bots, the ones already queued are drained and recorded as never dispatched
+            () = &mut deadline, if !deadline_passed => {
+                warn!("->> {:<12} - {}", "DISPATCH:: Deadline", "Run deadline passed, no more bots will be dispatched");
+                deadline_passed = true;
+                rx.close();
             }
-            bulk_runner_bots::BotStatus::NotReady(status) => {
-                info!("{:<12} - {:?}", "QUERY:: Not ready bot", status);
+            // Collect finished dispatches while waiting, so their outcome is logged as it happens
+            Some(joined) = in_flight.join_next(), if !in_flight.is_empty() => {
+                collect_record(joined, &mut records);
             }
         }
     }
-    drop(tx);
+
+    while let Some(joined) = in_flight.join_next().await {
+        collect_record(joined, &mut records);
+    }
+
+    DispatchReport::new(records)
 }
 
-pub async fn cli_dispatch(mut dispatch_bots: Vec<(Bot, String)>, total_bots: usize) {
-    let sempahore = Arc::new(tokio::sync::Semaphore::new(total_bots));
+fn collect_record(joined: std::result::Result<DispatchRecord, JoinError>, records: &mut Vec<DispatchRecord>) {
+    match joined {
+        Ok(record) => {
+            check_err(&record.outcome);
+            records.push(record);
+        }
+        Err(e) => error!("->> {:<12} - {:?}", "DISPATCH:: ERROR", Error::TokioJoinError(e)),
+    }
+}
 
-    let (dispatched_tx, mut dispatched_rx) = tokio::sync::mpsc::unbounded_channel();
+async fn dispatch_one(
+    bot: &Bot,
+    process_name: &str,
+    sempahore: &tokio::sync::Semaphore,
+    executor: &dyn DispatchExecutor,
+    timeouts: DispatchTimeouts,
+    retry: &RetryPolicy,
+    auth: &AutomateAuth,
+) -> DispatchRecord {
+    info!("->> {:<12} - {}: {}", "DISPATCH_ONE:: ", "Queued", &process_name);
+    let commander = match build_commander(bot, process_name, auth) {
+        Ok(commander) => commander,
+        // Never handed to AutomateC, the bot is reported with the reason instead
+        Err(e) => {
+            error!("->> {:<12} - {}: {}", "DISPATCH:: Rejected", &bot.name, e);
+            let now = Local::now();
+            return DispatchRecord {
+                bot_name:    bot.name.clone(),
+                process:     process_name.to_string(),
+                argv:        Vec::new(),
+                started_at:  now,
+                finished_at: now,
+                duration:    Duration::ZERO,
+                retried:     Vec::new(),
+                outcome:     Err(e),
+            };
+        }
+    };
+    let argv = commander.redacted();
+    let automatec_args = Vec::<String>::from(commander);
 
-    let blocking_task_handles = dispatch_bots
-        .iter_mut()
-        .map(|(bot, process_name)| {
-            let sempahore = sempahore.clone();
+    let started_at = Local::now();
+    let timer = Instant::now();
+    let mut retried = Vec::new();
 
-            let bot = bot.clone();
-            let process_name = process_name.clone();
-            let dispatched_tx = dispatched_tx.clone();
+    let outcome = loop {
+        let attempt_started_at = Local::now();
+        let attempt_timer = Instant::now();
+        let outcome =
+            attempt_one(bot, process_name, automatec_args.clone(), sempahore, executor, timeouts).await;
 
-            tokio::task::spawn_blocking(move || {
-                let res = threaded_dispatch(&bot, &process_name, sempahore.as_ref());
-                dispatched_tx.send(res).unwrap_or_default();
-                drop(sempahore);
-                drop(dispatched_tx);
-            })
-        })
-        .collect::<Vec<_>>();
+        let attempt = u32::try_from(retried.len() + 1).unwrap_or(u32::MAX);
+        let failed = match outcome {
+            Ok(outcome) if attempt < retry.max_attempts() && retry.is_retryable(&outcome) => outcome,
+            outcome => break outcome,
+        };
 
-    let t1 = tokio::spawn(async move {
-        for v in blocking_task_handles {
-            v.await.map_err(error::Error::TokioJoinError).unwrap_or_default();
+        let backoff = retry.backoff(attempt);
+        if timeouts
+            .deadline
+            .is_some_and(|deadline| deadline <= tokio::time::Instant::now() + backoff)
+        {
+            warn!("->> {:<12} - {}: {}", "DISPATCH:: RETRY", "Run deadline too close to retry", &bot.name);
+            break Ok(failed);
         }
-    });
-    drop(dispatched_tx);
-
-    let t2 = tokio::spawn(async move {
-        while let Some(res) = dispatched_rx.recv().await {
-            match res {
-                Ok(()) => info!("->> {:<12} - {}", "DISPATCH:: OK", "Bot ran successfully!"),
-                Err(e) => error!("->> {:<12} - {:?}", "DISPATCH:: ERROR", e),
+
+        warn!(
+            "->> {:<12} - {} failed on attempt {}/{}, retrying in {:.1?}",
+            "DISPATCH:: RETRY",
+            &bot.name,
+            attempt,
+            retry.max_attempts(),
+            backoff
+        );
+        retried.push(DispatchAttempt {
+            started_at: attempt_started_at,
+            duration: attempt_timer.elapsed(),
+            backoff,
+            outcome: failed,
+        });
+        tokio::time::sleep(backoff).await;
+    };
+
+    DispatchRecord {
+        bot_name: bot.name.clone(),
+        process: process_name.to_string(),
+        argv,
+        started_at,
+        finished_at: Local::now(),
+        duration: timer.elapsed(),
+        retried,
+        outcome,
+    }
+}
+
+/// A single attempt at dispatching `bot`, holding a slot only while the executor runs.
+async fn attempt_one(
+    bot: &Bot,
+    process_name: &str,
+    args: Vec<String>,
+    sempahore: &tokio::sync::Semaphore,
+    executor: &dyn DispatchExecutor,
+    timeouts: DispatchTimeouts,
+) -> Result<DispatchOutcome> {
+    // Waiting for a slot counts against the deadline, but not the per-dispatch timeout
+    let permit = match timeouts.deadline {
+        _ if timeouts.deadline_passed() => None,
+        Some(deadline) => tokio::time::timeout_at(deadline, sempahore.acquire()).await.ok(),
+        None => Some(sempahore.acquire().await),
+    };
+
+    match permit {
+        Some(Ok(_permit)) => {
+            let execution = executor.execute(bot, process_name, args);
+            match timeouts.limit_from_now() {
+                // Dropping the execution on timeout kills the child, and the permit is released on return
+                Some(limit) => {
+                    match tokio::time::timeout(limit, execution).await {
+                        Ok(outcome) => outcome.map_err(Error::from),
+                        Err(_) => Ok(DispatchOutcome::TimedOut(limit)),
+                    }
+                }
+                None => execution.await.map_err(Error::from),
             }
         }
-        dispatched_rx.close();
-        drop(dispatched_rx);
-    });
-
-    let _ = futures::future::join_all(vec![t1, t2]).await;
+        Some(Err(e)) => Err(Error::from(e)),
+        None => Err(Error::RunDeadlinePassed),
+    }
 }
 
-#[tokio::main]
-async fn threaded_dispatch(bot: &Bot, process_name: &str, sempahore: &tokio::sync::Semaphore) -> Result<()> {
-    info!("->> {:<12} - {}: {}", "THREADED_DISP:: ", "Spawn local", &process_name);
-    let permit = sempahore.acquire().await?;
-    let commander = crate::command_builder::AutomateBuilderBase::default()
-        .with_sso()
+/// Builds the `AutomateC` arguments used to run `process_name` on `bot`, signing in with `auth`.
+///
+/// # Errors
+/// Returns `Error::InvalidAutomateCommand` if the arguments aren't a command `AutomateC` would accept.
+pub fn build_commander(bot: &Bot, process_name: &str, auth: &AutomateAuth) -> Result<AutomateCCommander> {
+    let mut builder = AutomateBuilderBase::default();
+    auth.apply(&mut builder);
+    builder
         .with_process(process_name)
         .with_resource(&bot.name)
-        .build();
-
-    let res = bulk_runner_bots::dispatch(bot.name.clone(), commander.into()).await;
-    tokio::task::yield_now().await;
-    check_err(res);
-    drop(permit);
-
-    Ok(())
+        .try_build()
 }
 
-pub fn check_err(res: bulk_runner_bots::Result<()>) {
+pub fn check_err(res: &Result<DispatchOutcome>) {
     match res {
-        Ok(()) => info!("->> {:<12} - {}", "CHECK_ERR:: OK", "Bot ran successfully!"),
+        Ok(DispatchOutcome::Started(output)) => {
+            info!(
+                "->> {:<12} - {}: {}",
+                "CHECK_ERR:: OK",
+                "Bot ran successfully!",
+                output.session_id().unwrap_or("no session ID")
+            );
+        }
+        Ok(DispatchOutcome::Failed(output)) => {
+            error!(
+                "->> {:<12} - {}: {} - {}",
+                "CHECK_ERR:: FAIL",
+                "Bot failed to start",
+                output.status(),
+                output
+                    .failure_reason()
+                    .map(ToString::to_string)
+                    .unwrap_or_default()
+            );
+        }
+        Ok(DispatchOutcome::Skipped) => {
+            info!("->> {:<12} - {}", "CHECK_ERR:: SKIP", "Bot was not dispatched");
+        }
+        Ok(DispatchOutcome::TimedOut(limit)) => {
+            error!("->> {:<12} - {}: {:?}", "CHECK_ERR:: TIMEOUT", "Bot was killed after", limit);
+        }
         Err(e) => error!("->> {:<12} - {:?}", "CHECK_ERR:: ERROR", e),
     }
 }
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// How long dispatches may take, both each on its own and the run as a whole.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DispatchTimeouts {
+    /// A dispatch still running after this long is killed, and recorded as timed out.
+    pub per_dispatch: Option<Duration>,
+    /// Nothing is dispatched after this instant, and anything still running is killed.
+    pub deadline:     Option<Instant>,
+}
+
+impl DispatchTimeouts {
+    /// No limits, every dispatch runs for as long as it takes.
+    #[must_use]
+    pub fn unbounded() -> Self {
+        DispatchTimeouts::default()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn with_per_dispatch(mut self, per_dispatch: Option<Duration>) -> Self {
+        self.per_dispatch = per_dispatch;
+        self
+    }
+
+    /// Sets the deadline to `run_deadline` from now.
+    #[must_use]
+    #[inline]
+    pub fn with_run_deadline(mut self, run_deadline: Option<Duration>) -> Self {
+        self.deadline = run_deadline.map(|run_deadline| Instant::now() + run_deadline);
+        self
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn deadline_passed(&self) -> bool {
+        self.deadline.is_some_and(|deadline| deadline <= Instant::now())
+    }
+
+    /// How long a dispatch starting now may run for, the shorter of the per-dispatch timeout and the time left.
+    #[must_use]
+    pub fn limit_from_now(&self) -> Option<Duration> {
+        let left = self
+            .deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()));
+
+        match (self.per_dispatch, left) {
+            (Some(per_dispatch), Some(left)) => Some(per_dispatch.min(left)),
+            (per_dispatch, left) => per_dispatch.or(left),
+        }
+    }
+}
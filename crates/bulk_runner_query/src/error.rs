@@ -15,6 +15,9 @@ pub enum Error {
     #[error("Database error: {0}")]
     Database(#[from] deadpool_tiberius::SqlServerError),
 
+    #[error("Failed to get a pooled database connection: {0}")]
+    Pool(String),
+
     #[error("Deadpool failure during query run: {0}")]
     Deadpool(#[from] deadpool_tiberius::tiberius::error::Error),
 
@@ -27,6 +30,18 @@ pub enum Error {
     #[error("Bulk Runner Bots failure in query specific crate!: {0}")]
     BulkRunnerBots(#[from] bulk_runner_bots::Error),
 
+    #[error("Invalid limit '{0}', expected a number of bots or \"unlimited\"")]
+    InvalidRunLimit(String),
+
+    #[error("The bots file {} could not be read: {}", .0.display(), .1)]
+    InvalidBotsFile(std::path::PathBuf, String),
+
+    #[error("Invalid AutomateC command: {0}")]
+    InvalidAutomateCommand(String),
+
+    #[error("The run deadline passed before this bot could be dispatched")]
+    RunDeadlinePassed,
+
     #[error("Tokio Join error: {0}")]
     TokioJoinError(tokio::task::JoinError),
 }
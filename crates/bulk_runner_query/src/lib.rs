@@ -1,24 +1,34 @@
+mod automate_auth;
+mod bot_source;
 mod command_builder;
 mod db_info;
 mod dispatch;
+mod dispatch_timeouts;
 mod error;
 mod query_engine;
+mod report;
+mod retry_policy;
+mod run_limit;
+mod sql_validation;
 
-use tracing::{error, info};
+pub use bulk_runner_bots::REDACTED;
+use tracing::{error, info, warn};
 
-pub use crate::command_builder::AutomateBuilderBase;
-#[cfg(windows)]
-#[cfg(not(unix))]
-pub use crate::db_info::DbInfo;
-#[cfg(not(windows))]
-#[cfg(unix)]
-pub use crate::db_info::{sql_password_from_env, sql_user_from_env, DbInfo};
-pub use crate::dispatch::{cli_dispatch, query_database};
+pub use crate::automate_auth::AutomateAuth;
+pub use crate::bot_source::{BotSource, FileBotSource, NamedBotSource, SqlBotSource, StaticBotSource};
+pub use crate::command_builder::{AutomateBuilderBase, AutomateCCommander, InputType, ProcessInput};
+pub use crate::db_info::{sql_auth_method_from_env, sql_password_from_env, sql_user_from_env, DbInfo};
+pub use crate::dispatch::{build_commander, cli_dispatch, query_database, stream_dispatch};
+pub use crate::dispatch_timeouts::DispatchTimeouts;
 // use bulk_runner_bots::{BaseBot, Bot};
 
 // use tokio::sync::mpsc::UnboundedSender;
 pub use crate::error::Error;
 pub use crate::query_engine::QueryEngine;
+pub use crate::report::{DispatchAttempt, DispatchRecord, DispatchReport, FilteredBot};
+pub use crate::retry_policy::RetryPolicy;
+pub use crate::run_limit::RunLimit;
+pub use crate::sql_validation::{ColumnCheck, ColumnRole, SqlValidation};
 pub type Result<T> = std::result::Result<T, Error>;
 
 // use crate::prelude::*;
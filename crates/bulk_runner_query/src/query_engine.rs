@@ -1,10 +1,12 @@
-use bulk_runner_bots::BaseBot;
+use bulk_runner_bots::{BaseBot, ColumnMap};
 use deadpool_tiberius::tiberius::{Query, Row};
 use deadpool_tiberius::{Manager, Pool};
-use rayon::prelude::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use futures::TryStreamExt;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 
 use crate::db_info::DbInfo;
-use crate::Result;
+use crate::run_limit::RunLimit;
+use crate::{warn, Error, Result};
 
 pub struct QueryEngine {
     pub(crate) pool: Pool,
@@ -38,12 +40,30 @@ impl Default for QueryEngine {
 }
 
 impl QueryEngine {
-    pub(crate) fn new(db_info: DbInfo) -> Result<Self> {
-        let pool = Manager::new()
+    /// Builds the connection pool for the given database, no connection is made until the first query.
+    ///
+    /// # Errors
+    /// Returns an error if the pool cannot be created.
+    pub fn new(db_info: DbInfo) -> Result<Self> {
+        let mut manager = Manager::new()
             .host(db_info.host)
             .authentication(db_info.auth)
             .trust_cert()
-            .database(db_info.db)
+            .database(db_info.db);
+
+        if let Some(port) = db_info.port {
+            manager = manager.port(port);
+        }
+
+        // An explicit port wins, otherwise a named instance is looked up through the SQL Browser
+        if let Some(instance) = db_info.instance {
+            manager = manager.instance_name(instance);
+            if db_info.port.is_none() {
+                manager = manager.enable_sql_browser();
+            }
+        }
+
+        let pool = manager
             .max_size(8)
             .wait_timeout(2)
             .recycle_timeout(8)
@@ -53,28 +73,102 @@ impl QueryEngine {
         Ok(Self { pool })
     }
 
-    /// Retrieves bots from the database based on the provided SQL query and limit.
+    /// Retrieves every bot the provided SQL query returns,
+    /// reading each row by the column names in `columns`.
+    ///
+    /// `@P1` is bound to `limit`, so a `TOP (@P1)` caps the rows returned.
+    /// Rows that can't be read as a bot are logged and left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails, or doesn't return the mapped name and status columns.
+    pub async fn get_bots<S>(
+        &self,
+        parsed_file: S,
+        limit: RunLimit,
+        columns: &ColumnMap,
+    ) -> Result<Vec<BaseBot>>
+    where
+        S: AsRef<str> + Send + Sync,
+    {
+        warn_unhonoured(parsed_file.as_ref(), limit);
+
+        let rows = self.query(parsed_file.as_ref(), limit.as_bind()).await?;
+
+        let mut bots = Vec::new();
+        for row in &rows {
+            if let Some(base_bot) = read_row(row, columns)? {
+                bots.push(base_bot);
+            }
+        }
+
+        Ok(bots)
+    }
+
+    /// Streams bots from the database, handing each one to `on_bot` as soon as its row arrives
+    /// rather than waiting for the whole result set.
+    ///
+    /// `@P1` is bound to `limit`, so a `TOP (@P1)` caps the rows returned, and every row returned is handed over.
+    /// Rows that can't be read as a bot are logged and left out.
+    ///
+    /// Returns the number of rows that were read.
     ///
     /// # Errors
     ///
-    /// Returns an error if the query fails.
-    pub async fn get_bots<S>(&self, parsed_file: S, limit_total_runnable: usize) -> Result<Vec<BaseBot>>
+    /// Returns an error if the query fails, or doesn't return the mapped name and status columns.
+    /// Rows already handed to `on_bot` stay handed over.
+    pub async fn stream_bots<S, F>(
+        &self,
+        parsed_file: S,
+        limit: RunLimit,
+        columns: &ColumnMap,
+        mut on_bot: F,
+    ) -> Result<usize>
+    where
+        S: AsRef<str> + Send + Sync,
+        F: FnMut(BaseBot) + Send,
+    {
+        warn_unhonoured(parsed_file.as_ref(), limit);
+
+        let seen = self
+            .query_each(parsed_file.as_ref(), limit.as_bind(), |row| {
+                if let Some(base_bot) = read_row(&row, columns)? {
+                    on_bot(base_bot);
+                }
+                Ok(())
+            })
+            .await?;
+
+        Ok(seen)
+    }
+
+    /// Reads the names of the columns `sql` returns, without reading any rows.
+    ///
+    /// The query runs under `SET FMTONLY ON`, so SQL Server only describes its result set,
+    /// and with `@P1` bound to 0, so a `TOP (@P1)` returns nothing should `FMTONLY` be ignored.
+    ///
+    /// # Errors
+    /// Returns an error if the SQL doesn't compile, or the query fails.
+    pub async fn result_columns<S>(&self, sql: S) -> Result<Vec<String>>
     where
         S: AsRef<str> + Send + Sync,
     {
-        let limited_total_runnable = u8::try_from(if limit_total_runnable == 0 {
-            u8::MAX as usize
-        } else {
-            limit_total_runnable
-        })
-        .unwrap_or(u8::MAX);
-
-        Ok(self
-            .query(parsed_file.as_ref(), limited_total_runnable)
+        let mut con = self.pool.get().await.map_err(|e| Error::Pool(e.to_string()))?;
+
+        let sql = sql.as_ref().trim().trim_end_matches(';');
+        let mut query = Query::new(format!("SET FMTONLY ON; {sql}; SET FMTONLY OFF;"));
+        query.bind(0_i64);
+
+        let mut stream = query.query(&mut con).await?;
+        let columns = stream
+            .columns()
             .await?
-            .par_iter()
-            .map(BaseBot::from)
-            .collect::<Vec<BaseBot>>())
+            .map(|columns| columns.iter().map(|c| c.name().to_string()).collect())
+            .unwrap_or_default();
+        // Drained, so the connection goes back to the pool ready for the next query
+        stream.into_results().await?;
+
+        Ok(columns)
     }
 
     // Add pub methods here to access the run_query method
@@ -83,19 +177,26 @@ impl QueryEngine {
 
 #[async_trait::async_trait]
 pub trait Queryable {
-    async fn query<S>(&self, query: S, total_run_on: u8) -> Result<Vec<Row>>
+    async fn query<S>(&self, query: S, total_run_on: i64) -> Result<Vec<Row>>
     where
         S: AsRef<str> + Send + Sync;
+
+    /// Runs the query and calls `on_row` for every row as it comes off the wire,
+    /// returning how many rows were seen.
+    /// The first error returned by `on_row` stops the query, and is returned.
+    async fn query_each<S, F>(&self, query: S, total_run_on: i64, on_row: F) -> Result<usize>
+    where
+        S: AsRef<str> + Send + Sync,
+        F: FnMut(Row) -> Result<()> + Send;
 }
 
 #[async_trait::async_trait]
 impl Queryable for QueryEngine {
-    async fn query<S>(&self, query: S, limit_total_runnable: u8) -> Result<Vec<Row>>
+    async fn query<S>(&self, query: S, limit_total_runnable: i64) -> Result<Vec<Row>>
     where
         S: AsRef<str> + Send + Sync,
     {
-        #[rustfmt::skip]
-        let mut con = self.pool.get().await.expect("Failed to get pooled connection in run_query");
+        let mut con = self.pool.get().await.map_err(|e| Error::Pool(e.to_string()))?;
 
         let mut results = Query::new(query.as_ref());
         results.bind(limit_total_runnable);
@@ -104,6 +205,51 @@ impl Queryable for QueryEngine {
 
         Ok(results.into_par_iter().flat_map(|row| row).collect::<Vec<Row>>())
     }
+
+    async fn query_each<S, F>(&self, query: S, limit_total_runnable: i64, mut on_row: F) -> Result<usize>
+    where
+        S: AsRef<str> + Send + Sync,
+        F: FnMut(Row) -> Result<()> + Send,
+    {
+        let mut con = self.pool.get().await.map_err(|e| Error::Pool(e.to_string()))?;
+
+        let mut results = Query::new(query.as_ref());
+        results.bind(limit_total_runnable);
+
+        let mut rows = results.query(&mut con).await?.into_row_stream();
+
+        let mut seen = 0;
+        while let Some(row) = rows.try_next().await? {
+            seen += 1;
+            on_row(row)?;
+        }
+
+        Ok(seen)
+    }
+}
+
+/// Reads a bot from `row`, a row that can't be read is logged and `None` is returned.
+///
+/// A mapped column that is missing, or isn't text, means no row can be read, so that is returned as an error.
+fn read_row(row: &Row, columns: &ColumnMap) -> Result<Option<BaseBot>> {
+    match BaseBot::try_from_row(row, columns) {
+        Ok(base_bot) => Ok(Some(base_bot)),
+        Err(
+            e @ (bulk_runner_bots::Error::MissingColumn { .. }
+            | bulk_runner_bots::Error::MistypedColumn { .. }),
+        ) => Err(e.into()),
+        Err(e) => {
+            warn!("->> {:<12} - {}", "QUERY:: Rejected row", e);
+            Ok(None)
+        }
+    }
+}
+
+/// Logs why the SQL file can't honour the requested limit, if it can't.
+fn warn_unhonoured(sql: &str, limit: RunLimit) {
+    if let Some(reason) = limit.unhonoured_by(sql) {
+        warn!("->> {:<12} - {}", "QUERY:: Limit not honoured", reason);
+    }
 }
 
 impl From<DbInfo> for QueryEngine {
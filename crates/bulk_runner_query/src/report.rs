@@ -0,0 +1,299 @@
+use std::time::Duration;
+
+use bulk_runner_bots::{BotOutput, DispatchOutcome, FailureReason};
+use chrono::{DateTime, Local};
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+
+use crate::Result;
+
+/// What happened when a single bot was dispatched.
+#[derive(Debug)]
+pub struct DispatchRecord {
+    pub bot_name:    String,
+    pub process:     String,
+    /// The `AutomateC` arguments used, with any secrets redacted.
+    pub argv:        Vec<String>,
+    pub started_at:  DateTime<Local>,
+    pub finished_at: DateTime<Local>,
+    pub duration:    Duration,
+    /// Earlier attempts that failed and were retried, oldest first.
+    pub retried:     Vec<DispatchAttempt>,
+    pub outcome:     Result<DispatchOutcome>,
+}
+
+/// A failed attempt at dispatching a bot, that was tried again after `backoff`.
+#[derive(Debug)]
+pub struct DispatchAttempt {
+    pub started_at: DateTime<Local>,
+    pub duration:   Duration,
+    pub backoff:    Duration,
+    pub outcome:    DispatchOutcome,
+}
+
+impl DispatchRecord {
+    /// The exit code `AutomateC` returned, if it ran and exited normally.
+    #[must_use]
+    #[inline]
+    pub fn exit_code(&self) -> Option<i32> {
+        self.output().and_then(|output| output.status().code())
+    }
+
+    /// The captured stdout/stderr and exit status, if a process was run.
+    #[must_use]
+    #[inline]
+    pub fn output(&self) -> Option<&BotOutput> {
+        self.outcome.as_ref().ok().and_then(DispatchOutcome::output)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn is_success(&self) -> bool {
+        self.outcome.as_ref().is_ok_and(DispatchOutcome::is_success)
+    }
+
+    /// The Blue Prism session `AutomateC` started, to find the run in Control Room.
+    #[must_use]
+    #[inline]
+    pub fn session_id(&self) -> Option<&str> {
+        self.output().and_then(BotOutput::session_id)
+    }
+
+    /// Why `AutomateC` failed to start the process, if it ran and failed.
+    #[must_use]
+    #[inline]
+    pub fn failure_reason(&self) -> Option<&FailureReason> {
+        self.output().and_then(BotOutput::failure_reason)
+    }
+
+    /// How many times the bot was tried, including the final attempt.
+    #[must_use]
+    #[inline]
+    pub fn attempts(&self) -> usize {
+        self.retried.len() + 1
+    }
+
+    /// A short, human readable label for the outcome.
+    #[must_use]
+    pub fn outcome_label(&self) -> String {
+        match &self.outcome {
+            Ok(outcome) => outcome_label(outcome),
+            Err(e) => format!("ERROR: {e}"),
+        }
+    }
+
+    /// The machine-readable outcome, its `DispatchOutcome::kind` or "ERROR" when none was produced.
+    #[must_use]
+    #[inline]
+    pub fn outcome_kind(&self) -> &'static str {
+        self.outcome.as_ref().map_or("ERROR", DispatchOutcome::kind)
+    }
+}
+
+impl DispatchAttempt {
+    /// The exit code `AutomateC` returned on this attempt, if it exited normally.
+    #[must_use]
+    #[inline]
+    pub fn exit_code(&self) -> Option<i32> {
+        self.outcome.output().and_then(|output| output.status().code())
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn outcome_label(&self) -> String {
+        outcome_label(&self.outcome)
+    }
+}
+
+/// A ready bot that was kept out of the run by a filter rule, and so never dispatched.
+#[derive(Clone, Debug, Serialize)]
+pub struct FilteredBot {
+    pub bot_name: String,
+    pub status:   String,
+    /// The rule that filtered it out.
+    pub rule:     String,
+}
+
+/// One `DispatchRecord` per bot that `cli_dispatch` was handed,
+/// and the bots that were filtered out before dispatch.
+#[derive(Debug, Default)]
+pub struct DispatchReport {
+    records:  Vec<DispatchRecord>,
+    filtered: Vec<FilteredBot>,
+}
+
+impl DispatchReport {
+    #[must_use]
+    pub fn new(mut records: Vec<DispatchRecord>) -> Self {
+        records.sort_by(|a, b| a.started_at.cmp(&b.started_at).then(a.bot_name.cmp(&b.bot_name)));
+        DispatchReport {
+            records,
+            filtered: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn with_filtered(mut self, filtered: Vec<FilteredBot>) -> Self {
+        self.filtered = filtered;
+        self
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn records(&self) -> &[DispatchRecord] {
+        &self.records
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn filtered(&self) -> &[FilteredBot] {
+        &self.filtered
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Number of bots `AutomateC` reported as started.
+    #[must_use]
+    pub fn succeeded(&self) -> usize {
+        self.records.iter().filter(|r| r.is_success()).count()
+    }
+
+    /// Number of bots where `AutomateC` ran but exited non-zero.
+    #[must_use]
+    pub fn failed(&self) -> usize {
+        self.records
+            .iter()
+            .filter(|r| matches!(r.outcome, Ok(DispatchOutcome::Failed(_))))
+            .count()
+    }
+
+    /// Number of bots whose `AutomateC` was killed for running past its timeout.
+    #[must_use]
+    pub fn timed_out(&self) -> usize {
+        self.records
+            .iter()
+            .filter(|r| matches!(r.outcome, Ok(DispatchOutcome::TimedOut(_))))
+            .count()
+    }
+
+    /// Number of bots that were intentionally not dispatched.
+    #[must_use]
+    pub fn skipped(&self) -> usize {
+        self.records
+            .iter()
+            .filter(|r| matches!(r.outcome, Ok(DispatchOutcome::Skipped)))
+            .count()
+    }
+
+    /// Number of bots that needed more than one attempt, whatever their final outcome.
+    #[must_use]
+    pub fn retried(&self) -> usize {
+        self.records.iter().filter(|r| !r.retried.is_empty()).count()
+    }
+
+    /// Number of bots where no outcome could be produced at all.
+    #[must_use]
+    pub fn errored(&self) -> usize {
+        self.records.iter().filter(|r| r.outcome.is_err()).count()
+    }
+}
+
+fn outcome_label(outcome: &DispatchOutcome) -> String {
+    match outcome {
+        DispatchOutcome::Started(_) => "STARTED".to_string(),
+        DispatchOutcome::Failed(_) => "FAILED".to_string(),
+        DispatchOutcome::Skipped => "SKIPPED".to_string(),
+        DispatchOutcome::TimedOut(limit) => format!("TIMED OUT after {limit:.0?}"),
+    }
+}
+
+impl Serialize for DispatchRecord {
+    /// The bot, its timings and attempts, with the outcome's fields alongside,
+    /// or an "ERROR" outcome with the `error` when none was produced.
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut record = serializer.serialize_map(None)?;
+        record.serialize_entry("bot", &self.bot_name)?;
+        record.serialize_entry("process", &self.process)?;
+        record.serialize_entry("argv", &self.argv)?;
+        record.serialize_entry("started_at", &self.started_at.to_rfc3339())?;
+        record.serialize_entry("finished_at", &self.finished_at.to_rfc3339())?;
+        record.serialize_entry("duration_ms", &millis(self.duration))?;
+        record.serialize_entry("attempts", &self.attempts())?;
+        match &self.outcome {
+            Ok(outcome) => outcome.serialize_fields(&mut record)?,
+            Err(e) => {
+                record.serialize_entry("outcome", self.outcome_kind())?;
+                record.serialize_entry("error", &e.to_string())?;
+            }
+        }
+        record.serialize_entry("retried", &self.retried)?;
+        record.end()
+    }
+}
+
+impl Serialize for DispatchAttempt {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut attempt = serializer.serialize_map(None)?;
+        attempt.serialize_entry("started_at", &self.started_at.to_rfc3339())?;
+        attempt.serialize_entry("duration_ms", &millis(self.duration))?;
+        attempt.serialize_entry("backoff_ms", &millis(self.backoff))?;
+        self.outcome.serialize_fields(&mut attempt)?;
+        attempt.end()
+    }
+}
+
+/// The counts summarised at the bottom of the report.
+#[derive(Serialize)]
+struct Totals {
+    dispatched: usize,
+    started:    usize,
+    failed:     usize,
+    timed_out:  usize,
+    skipped:    usize,
+    errored:    usize,
+    retried:    usize,
+    filtered:   usize,
+}
+
+impl Serialize for DispatchReport {
+    /// The totals, then every record and filtered bot.
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let totals = Totals {
+            dispatched: self.len(),
+            started:    self.succeeded(),
+            failed:     self.failed(),
+            timed_out:  self.timed_out(),
+            skipped:    self.skipped(),
+            errored:    self.errored(),
+            retried:    self.retried(),
+            filtered:   self.filtered.len(),
+        };
+
+        let mut report = serializer.serialize_map(Some(3))?;
+        report.serialize_entry("totals", &totals)?;
+        report.serialize_entry("records", &self.records)?;
+        report.serialize_entry("filtered", &self.filtered)?;
+        report.end()
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+impl FromIterator<DispatchRecord> for DispatchReport {
+    fn from_iter<T: IntoIterator<Item = DispatchRecord>>(iter: T) -> Self {
+        DispatchReport::new(iter.into_iter().collect())
+    }
+}
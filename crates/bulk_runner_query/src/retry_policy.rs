@@ -0,0 +1,121 @@
+use std::hash::BuildHasher;
+use std::time::{Duration, Instant};
+
+use bulk_runner_bots::DispatchOutcome;
+use regex::Regex;
+
+/// When, and how often, a dispatch `AutomateC` failed is tried again.
+///
+/// Only `DispatchOutcome::Failed` is retried. With no exit codes or stderr patterns given every failure is
+/// retryable, otherwise only failures matching one of them are. Timed out dispatches are never retried.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    max_attempts:    u32,
+    initial_backoff: Duration,
+    max_backoff:     Duration,
+    exit_codes:      Vec<i32>,
+    stderr_patterns: Vec<Regex>,
+}
+
+impl Default for RetryPolicy {
+    /// Every bot is tried once.
+    #[inline]
+    fn default() -> Self {
+        RetryPolicy::new(1)
+    }
+}
+
+impl RetryPolicy {
+    /// Waited before the first retry, doubling on each one after.
+    pub const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_secs(5);
+    /// The longest wait between two attempts, before jitter.
+    pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+    /// Tries each bot up to `max_attempts` times in all, at least once.
+    #[must_use]
+    pub fn new(max_attempts: u32) -> Self {
+        RetryPolicy {
+            max_attempts:    max_attempts.max(1),
+            initial_backoff: Self::DEFAULT_INITIAL_BACKOFF,
+            max_backoff:     Self::DEFAULT_MAX_BACKOFF,
+            exit_codes:      Vec::new(),
+            stderr_patterns: Vec::new(),
+        }
+    }
+
+    /// Waits `initial` before the first retry, doubling each time up to `max`.
+    #[must_use]
+    #[inline]
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Only retries failures exiting with one of `exit_codes`, or matching a stderr pattern.
+    #[must_use]
+    #[inline]
+    pub fn with_exit_codes(mut self, exit_codes: Vec<i32>) -> Self {
+        self.exit_codes = exit_codes;
+        self
+    }
+
+    /// Only retries failures whose stderr matches one of `stderr_patterns`, or exiting with a retryable code.
+    #[must_use]
+    #[inline]
+    pub fn with_stderr_patterns(mut self, stderr_patterns: Vec<Regex>) -> Self {
+        self.stderr_patterns = stderr_patterns;
+        self
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Whether `outcome` is a failure worth trying again.
+    #[must_use]
+    pub fn is_retryable(&self, outcome: &DispatchOutcome) -> bool {
+        let DispatchOutcome::Failed(output) = outcome else {
+            return false;
+        };
+
+        if self.exit_codes.is_empty() && self.stderr_patterns.is_empty() {
+            return true;
+        }
+
+        output
+            .status()
+            .code()
+            .is_some_and(|code| self.exit_codes.contains(&code))
+            || self
+                .stderr_patterns
+                .iter()
+                .any(|pattern| pattern.is_match(output.stderr()))
+    }
+
+    /// How long to wait after the failed `attempt` (counting from 1) before trying again.
+    ///
+    /// Exponential, capped at the max backoff, with the upper half jittered so bots failing
+    /// together don't all retry together.
+    #[must_use]
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let doublings = attempt.saturating_sub(1).min(31);
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(1 << doublings)
+            .min(self.max_backoff);
+
+        backoff / 2 + jitter(backoff / 2)
+    }
+}
+
+/// A random duration up to `limit`.
+fn jitter(limit: Duration) -> Duration {
+    // Each RandomState is randomly keyed, which is all the randomness a backoff needs
+    let random = std::collections::hash_map::RandomState::new().hash_one(Instant::now());
+    let nanos = u64::try_from(limit.as_nanos()).unwrap_or(u64::MAX);
+
+    Duration::from_nanos(random % nanos.saturating_add(1))
+}
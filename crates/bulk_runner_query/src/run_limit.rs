@@ -0,0 +1,151 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+/// How many bots a run may dispatch, only bots that are dispatched count toward it.
+///
+/// Bound to `@P1` in the SQL file as a `bigint`, `Unlimited` binds `i64::MAX`
+/// so `SELECT TOP (@P1)` keeps working without a cap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawRunLimit", into = "RawRunLimit")]
+pub enum RunLimit {
+    Limited(u64),
+    Unlimited,
+}
+
+impl RunLimit {
+    /// The value bound to `@P1`.
+    #[must_use]
+    #[inline]
+    pub fn as_bind(self) -> i64 {
+        match self {
+            RunLimit::Limited(limit) => i64::try_from(limit).unwrap_or(i64::MAX),
+            RunLimit::Unlimited => i64::MAX,
+        }
+    }
+
+    /// The most rows that may be taken from the query, `None` when unlimited.
+    #[must_use]
+    #[inline]
+    pub fn max_rows(self) -> Option<usize> {
+        match self {
+            RunLimit::Limited(limit) => Some(usize::try_from(limit).unwrap_or(usize::MAX)),
+            RunLimit::Unlimited => None,
+        }
+    }
+
+    /// Whether the `count`'th bot (counting from 1) is within the limit.
+    #[must_use]
+    #[inline]
+    pub fn allows(self, count: usize) -> bool {
+        self.max_rows().is_none_or(|max| count <= max)
+    }
+
+    /// Explains why the SQL file can't return as many rows as this limit asks for, if it can't.
+    ///
+    /// A literal `TOP n` smaller than the limit caps the run at `n` bots.
+    #[must_use]
+    pub fn unhonoured_by(self, sql: &str) -> Option<String> {
+        literal_top(&sql.to_lowercase())
+            .filter(|&top| self.max_rows().is_none_or(|max| top < max))
+            .map(|top| {
+                format!("the SQL file selects a literal TOP {top}, so at most {top} of the requested {self} bots can be run")
+            })
+    }
+}
+
+/// Finds the first `TOP n` / `TOP (n)` with a literal row count in an already lowercased query.
+fn literal_top(sql: &str) -> Option<usize> {
+    sql.match_indices("top").find_map(|(at, _)| {
+        let starts_word = sql[..at]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !c.is_alphanumeric() && c != '_');
+        if !starts_word {
+            return None;
+        }
+
+        let rest = &sql[at + "top".len()..];
+        if !rest.starts_with(|c: char| c.is_whitespace() || c == '(') {
+            return None;
+        }
+
+        let rest = rest.trim_start().trim_start_matches('(').trim_start();
+        let digits = rest.split(|c: char| !c.is_ascii_digit()).next()?;
+        digits.parse().ok()
+    })
+}
+
+impl Default for RunLimit {
+    #[inline]
+    fn default() -> Self {
+        RunLimit::Limited(30)
+    }
+}
+
+impl From<u64> for RunLimit {
+    /// A limit of 0 has always meant "as many as the query returns".
+    #[inline]
+    fn from(limit: u64) -> Self {
+        match limit {
+            0 => RunLimit::Unlimited,
+            limit => RunLimit::Limited(limit),
+        }
+    }
+}
+
+impl FromStr for RunLimit {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "unlimited" | "all" => Ok(RunLimit::Unlimited),
+            number => {
+                number
+                    .parse::<u64>()
+                    .map(RunLimit::from)
+                    .map_err(|_| Error::InvalidRunLimit(value.to_string()))
+            }
+        }
+    }
+}
+
+impl Display for RunLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunLimit::Limited(limit) => write!(f, "{limit}"),
+            RunLimit::Unlimited => f.write_str("unlimited"),
+        }
+    }
+}
+
+/// How a `RunLimit` is written in a config file, a number or the word "unlimited".
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum RawRunLimit {
+    Count(u64),
+    Word(String),
+}
+
+impl TryFrom<RawRunLimit> for RunLimit {
+    type Error = Error;
+
+    fn try_from(raw: RawRunLimit) -> Result<Self, Self::Error> {
+        match raw {
+            RawRunLimit::Count(limit) => Ok(RunLimit::from(limit)),
+            RawRunLimit::Word(word) => word.parse(),
+        }
+    }
+}
+
+impl From<RunLimit> for RawRunLimit {
+    fn from(limit: RunLimit) -> Self {
+        match limit {
+            RunLimit::Limited(limit) => RawRunLimit::Count(limit),
+            RunLimit::Unlimited => RawRunLimit::Word(limit.to_string()),
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::fmt::{self, Display};
+
+use bulk_runner_bots::ColumnMap;
+use serde::Serialize;
+
+/// What a mapped column is read as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnRole {
+    Name,
+    Status,
+    /// Carried along on the bot when returned, so never required.
+    Extra,
+}
+
+impl Display for ColumnRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnRole::Name => f.write_str("name"),
+            ColumnRole::Status => f.write_str("status"),
+            ColumnRole::Extra => f.write_str("extra"),
+        }
+    }
+}
+
+/// A column of the `ColumnMap`, and whether the SQL returns it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ColumnCheck {
+    pub column:   String,
+    pub role:     ColumnRole,
+    pub returned: bool,
+}
+
+/// The columns the SQL file returns, checked against the `ColumnMap` the bots are read with.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SqlValidation {
+    returned: Vec<String>,
+    checks:   Vec<ColumnCheck>,
+}
+
+impl SqlValidation {
+    /// Checks every column of `columns` is among those `returned`, matched case-insensitively like a row is read.
+    #[must_use]
+    pub fn new(returned: Vec<String>, columns: &ColumnMap) -> Self {
+        let check = |column: &str, role| {
+            ColumnCheck {
+                column: column.to_string(),
+                role,
+                returned: returned.iter().any(|r| r.eq_ignore_ascii_case(column)),
+            }
+        };
+
+        let checks = [
+            (columns.name(), ColumnRole::Name),
+            (columns.status(), ColumnRole::Status),
+        ]
+        .into_iter()
+        .chain(
+            columns
+                .extras()
+                .iter()
+                .map(|extra| (extra.as_str(), ColumnRole::Extra)),
+        )
+        .map(|(column, role)| check(column, role))
+        .collect();
+
+        SqlValidation { returned, checks }
+    }
+
+    /// The columns the SQL returns, in order.
+    #[must_use]
+    #[inline]
+    pub fn returned(&self) -> &[String] {
+        &self.returned
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn checks(&self) -> &[ColumnCheck] {
+        &self.checks
+    }
+
+    /// The name or status columns the SQL doesn't return, without which no bot can be read.
+    #[must_use]
+    pub fn missing_required(&self) -> Vec<&str> {
+        self.missing(|role| role != ColumnRole::Extra)
+    }
+
+    /// The extra columns the SQL doesn't return, the bots are read without them.
+    #[must_use]
+    pub fn missing_extras(&self) -> Vec<&str> {
+        self.missing(|role| role == ColumnRole::Extra)
+    }
+
+    /// Whether bots can be read from what the SQL returns.
+    #[must_use]
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.missing_required().is_empty()
+    }
+
+    fn missing(&self, role: impl Fn(ColumnRole) -> bool) -> Vec<&str> {
+        self.checks
+            .iter()
+            .filter(|check| !check.returned && role(check.role))
+            .map(|check| check.column.as_str())
+            .collect()
+    }
+}
@@ -1,8 +1,29 @@
 use std::str::FromStr;
+use std::time::Duration;
 
-use clap::{Parser, ValueEnum};
+use bulk_runner_bots::{BaseBot, Bot, BotStatusReady, ColumnMap};
+use bulk_runner_query::{
+    AutomateAuth,
+    BotSource,
+    DbInfo,
+    FileBotSource,
+    NamedBotSource,
+    RetryPolicy,
+    SqlBotSource,
+    StaticBotSource,
+    REDACTED,
+};
+use clap::{Parser, Subcommand, ValueEnum};
+use deadpool_tiberius::tiberius::AuthMethod;
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
 
+use crate::config::{ConfigFile, Profile, ResolvedConfig, DEFAULT_CONFIG_FILE};
+use crate::credentials::PasswordSource;
+use crate::filter::{BotFilter, NamePattern};
+use crate::logging::Logging;
 use crate::prelude::*;
+use crate::process_map::ProcessMap;
 use crate::Result;
 
 #[derive(Parser, Debug)]
@@ -16,36 +37,248 @@ use crate::Result;
     This is particularly useful during change over periods where multiple bots need to be transitioned from one process to another.",
     version = std::env!("CARGO_PKG_VERSION"),
     arg_required_else_help = true,
+    group = clap::ArgGroup::new("resource_list").multiple(true),
     styles=get_styles()
 )]
+// Each bool is an independent on/off flag
+#[allow(clippy::struct_excessive_bools)]
 pub struct Cli {
     /// The process to run on all the bots pulled by the SQL query.
-    #[arg(index = 1, help = "The process to run the bots on.", value_hint = clap::ValueHint::Other)]
-    pub process: String,
+    /// Optional when every bot is given its own process by `--process-map` or `--process-column`,
+    /// otherwise it is the process for any bot they don't map.
+    #[arg(index = 1, help = "The process to run the bots on.", required = false, value_hint = clap::ValueHint::Other)]
+    pub process: Option<String>,
+
+    /// What to do, running the process when no subcommand is given.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Optional path to a file assigning a process to each resource, for changeovers onto several processes.
+    /// A .csv file with `resource` and `process` columns, or a .toml file mapping each process to a list of resources.
+    #[arg(long = "process-map", global = true, help = "A .csv or .toml file mapping resources to processes.", required = false, value_hint = clap::ValueHint::FilePath)]
+    pub process_map: Option<PathBuf>,
+
+    /// Optional SQL (or bots file) column holding the process to run on each resource.
+    /// It is read along with the other columns, and loses out to `--process-map` for any resource both map.
+    #[arg(long = "process-column", global = true, help = "The SQL column holding each resource's process.", required = false, value_hint = clap::ValueHint::Other)]
+    pub process_column: Option<String>,
 
     /// The number of bots to run concurrently.
     /// Limits the stress-load on the machine running the cli
-    #[arg(short = 'c', long = "concurrency_limit", default_value = "30", value_hint = clap::ValueHint::Other, long_help = "The number of bots to run concurrently. Limits the stress-load on the machine running the cli.")]
-    pub concurrency_limit: usize,
+    #[arg(short = 'c', long = "concurrency_limit", global = true, value_hint = clap::ValueHint::Other, long_help = "The number of bots to run concurrently. Limits the stress-load on the machine running the cli. [default: 30]")]
+    pub concurrency_limit: Option<usize>,
 
     /// The total number of bots of which the process will be dispatched for.
-    #[arg(short = 'l', long = "limit_total_runnable", default_value = "30", value_hint = clap::ValueHint::Other, long_help = "The total number of bots of which the process will be dispatched for.")]
-    pub limit_total_runnable: usize,
+    /// Accepts "unlimited" (or 0) to dispatch for every bot the query returns.
+    #[arg(short = 'l', long = "limit_total_runnable", global = true, value_hint = clap::ValueHint::Other, long_help = "The total number of bots of which the process will be dispatched for. Use \"unlimited\" (or 0) for no limit. [default: 30]")]
+    pub limit_total_runnable: Option<RunLimit>,
 
     /// Optional path to a SQL file to pull the bots from.
-    /// If not provided, the default value is "bots.sql".
+    /// If not provided, the profile's `sql_file` is used, otherwise the default value is "bots.sql".
     /// And is looked for in the current working directory of the binary.
-    #[arg(short = 'f', long = "file", help = "The path to the SQL file.", required = false, default_value = "bots.sql", value_hint = clap::ValueHint::FilePath
+    #[arg(short = 'f', long = "file", global = true, help = "The path to the SQL file.", required = false, value_hint = clap::ValueHint::FilePath
     )]
     sql_file: Option<PathBuf>,
 
+    /// Optional path to an exported resource list (.csv with a header row, or .json array of objects)
+    /// to read the bots from instead of the database, for when the Blue Prism database is unreachable.
+    #[arg(long = "bots-file", global = true, help = "Read the bots from a .csv or .json file instead of the database.", required = false, value_hint = clap::ValueHint::FilePath)]
+    pub bots_file: Option<PathBuf>,
+
+    /// Optional resources to run on, only these are dispatched for.
+    /// Each is still checked against the live status read from the database (or bots file),
+    /// unless `--skip-status-check` is given. Can be repeated, or given as a comma separated list.
+    #[arg(long = "resources", global = true, help = "Only run on these resources.", required = false, value_delimiter = ',', group = "resource_list", value_hint = clap::ValueHint::Other)]
+    pub resources: Vec<String>,
+
+    /// Optional path to a file listing resources to run on, one per line.
+    /// Blank lines and lines starting with '#' are ignored, combines with `--resources`.
+    #[arg(long = "resources-file", global = true, help = "Only run on the resources listed in this file.", required = false, group = "resource_list", value_hint = clap::ValueHint::FilePath)]
+    pub resources_file: Option<PathBuf>,
+
+    /// Dispatches for the named resources without reading their status, each is assumed idle.
+    /// The database (or bots file) isn't read at all, for when the status is known to be wrong or unavailable.
+    #[arg(
+        long = "skip-status-check",
+        global = true,
+        help = "Run on the named resources without checking their status.",
+        required = false,
+        default_value_t = false,
+        requires = "resource_list"
+    )]
+    pub skip_status_check: bool,
+
+    /// Optional patterns a resource name must match to be dispatched for, a glob ("BOT0*")
+    /// or a regex prefixed with "re:". Can be repeated, a bot matching any of them is included.
+    /// Replaces the profile's `include` when given.
+    #[arg(long = "include", global = true, help = "Only run on resources matching this glob (or re:regex).", required = false, value_hint = clap::ValueHint::Other)]
+    pub include: Vec<NamePattern>,
+
+    /// Optional patterns keeping matching resources out of the run, a glob ("VIP*") or a regex prefixed with "re:".
+    /// Can be repeated, and adds to the profile's `exclude` rather than replacing it.
+    #[arg(long = "exclude", global = true, help = "Never run on resources matching this glob (or re:regex).", required = false, value_hint = clap::ValueHint::Other)]
+    pub exclude: Vec<NamePattern>,
+
+    /// Optional name of the SQL column holding the resource name, defaults to "name".
+    #[arg(long = "name-column", global = true, help = "The SQL column holding the resource name.", required = false, value_hint = clap::ValueHint::Other)]
+    pub name_column: Option<String>,
+
+    /// Optional name of the SQL column holding the resource status, defaults to "status".
+    #[arg(long = "status-column", global = true, help = "The SQL column holding the resource status.", required = false, value_hint = clap::ValueHint::Other)]
+    pub status_column: Option<String>,
+
+    /// Optional extra SQL columns to carry along on each bot, when the query returns them.
+    /// Can be repeated, or given as a comma separated list.
+    #[arg(long = "extra-column", global = true, help = "Extra SQL columns to read for each bot.", required = false, value_delimiter = ',', value_hint = clap::ValueHint::Other)]
+    pub extra_columns: Vec<String>,
+
+    /// Optional path to a TOML config file holding named environment profiles.
+    /// If not provided, `bulk_runner.toml` is used when it exists in the current working directory.
+    #[arg(long = "config", global = true, env = "BULK_RUNNER_CONFIG", help = "The path to the config file.", required = false, value_hint = clap::ValueHint::FilePath)]
+    pub config: Option<PathBuf>,
+
+    /// Optional name of the config file profile to use (e.g. "prod", "uat", "dr").
+    /// If not provided, the config file's `default_profile` is used, if it has one.
+    /// Any flag given on the command line overrides the profile's value.
+    #[arg(long = "profile", global = true, env = "BULK_RUNNER_PROFILE", help = "The config profile to use.", required = false, value_hint = clap::ValueHint::Other)]
+    pub profile: Option<String>,
+
+    /// Prints the fully resolved configuration (flags, profile and defaults combined) and exits.
+    #[arg(
+        long = "print-config",
+        global = true,
+        help = "Print the resolved configuration and exit.",
+        required = false,
+        default_value_t = false
+    )]
+    pub print_config: bool,
+
+    /// Optional SQL Server host to query the bots from.
+    /// If not provided, the production host is used.
+    #[arg(long = "db-host", global = true, help = "The SQL Server host to query.", required = false, value_hint = clap::ValueHint::Hostname)]
+    pub db_host: Option<String>,
+
+    /// Optional name of the Blue Prism database on the host.
+    /// If not provided, the production database is used.
+    #[arg(long = "db-name", global = true, help = "The Blue Prism database name.", required = false, value_hint = clap::ValueHint::Other)]
+    pub db_name: Option<String>,
+
+    /// Optional TCP port of the SQL Server, defaults to 1433.
+    /// When given alongside `--db-instance`, the SQL Browser lookup is skipped.
+    #[arg(long = "db-port", global = true, help = "The SQL Server port.", required = false, value_hint = clap::ValueHint::Other)]
+    pub db_port: Option<u16>,
+
+    /// Optional named SQL Server instance, resolved through the SQL Browser service.
+    #[arg(long = "db-instance", global = true, help = "The named SQL Server instance.", required = false, value_hint = clap::ValueHint::Other)]
+    pub db_instance: Option<String>,
+
+    /// How to authenticate against the SQL Server.
+    /// "INTEGRATED" uses the current Windows login (Windows only),
+    /// "SQL" uses a SQL login, with the password read from the `PROD_SQL_PASSWORD` environment variable.
+    /// If not provided, the default is "INTEGRATED" on Windows and "SQL" elsewhere.
+    #[arg(value_enum, name = "db-auth", long = "db-auth", global = true, help = "The SQL Server authentication mode.", required = false, value_hint = clap::ValueHint::Other)]
+    pub db_auth: Option<DbAuthMode>,
+
+    /// The SQL login used with "SQL" authentication.
+    #[arg(long = "db-user", global = true, env = "PROD_SQL_USER", help = "The SQL login to authenticate with.", required = false, value_hint = clap::ValueHint::Username)]
+    pub db_user: Option<String>,
+
+    /// Optional path to the `AutomateC` executable used to dispatch the bots.
+    /// Useful when several Blue Prism versions are installed side by side.
+    /// If not provided, the default Blue Prism install location is used.
+    #[arg(long = "automatec-path", global = true, env = "AUTOMATEC_PATH", help = "The path to the AutomateC executable.", required = false, value_hint = clap::ValueHint::FilePath)]
+    pub automatec_path: Option<PathBuf>,
+
+    /// How `AutomateC` signs in to Blue Prism.
+    /// "SSO" signs in as the current Windows user, "USER" signs in with `--automate-user` and a password
+    /// read from the `AUTOMATEC_PASSWORD` environment variable, `--automate-password-file` or `--automate-password-prompt`.
+    /// If not provided, the default is "SSO".
+    #[arg(value_enum, name = "automate-auth", long = "automate-auth", global = true, help = "How AutomateC signs in: SSO or USER.", required = false, value_hint = clap::ValueHint::Other)]
+    pub automate_auth: Option<AutomateAuthMode>,
+
+    /// The Blue Prism user `AutomateC` signs in as with "USER" authentication.
+    #[arg(long = "automate-user", global = true, env = "AUTOMATEC_USER", help = "The Blue Prism user AutomateC signs in as.", required = false, value_hint = clap::ValueHint::Username)]
+    pub automate_user: Option<String>,
+
+    /// Optional file holding the `AutomateC` password on its first line.
+    /// It must only be readable by its owner (`chmod 600`).
+    #[arg(long = "automate-password-file", global = true, help = "Read the AutomateC password from this owner-only file.", required = false, value_hint = clap::ValueHint::FilePath)]
+    pub automate_password_file: Option<PathBuf>,
+
+    /// Asks for the `AutomateC` password on the terminal (or reads a line from stdin when piped) before the run starts.
+    #[arg(
+        long = "automate-password-prompt",
+        global = true,
+        help = "Prompt for the AutomateC password.",
+        required = false,
+        default_value_t = false,
+        conflicts_with = "automate_password_file"
+    )]
+    pub automate_password_prompt: bool,
+
+    /// Runs the SQL query and prints the `AutomateC` invocations that would be made,
+    /// then exits without dispatching anything.
+    #[arg(
+        short = 'n',
+        long = "dry-run",
+        global = true,
+        help = "Print the dispatch plan without running anything.",
+        required = false,
+        default_value_t = false
+    )]
+    pub dry_run: bool,
+
+    /// The format plans, bot listings and run results are printed in.
+    /// If not provided, the default value is "TABLE".
+    #[arg(value_enum, name = "output", short = 'o', long = "output", alias = "plan-format", global = true, help = "The format of the plan, listing or run results.", required = false, default_value = "TABLE", value_hint = clap::ValueHint::Other)]
+    pub output_format: Option<OutputFormat>,
+
+    /// Optional path to write the plan, listing or run results to, instead of stdout.
+    #[arg(long = "output-file", global = true, help = "The path to write the output to, instead of stdout.", required = false, value_hint = clap::ValueHint::FilePath)]
+    pub output_file: Option<PathBuf>,
+
+    /// Optional number of seconds a single `AutomateC` dispatch may run before it is killed and recorded as timed out.
+    /// Use 0 for no timeout. [default: 300]
+    #[arg(long = "dispatch-timeout", global = true, help = "Seconds before a hung dispatch is killed, 0 for none.", required = false, value_hint = clap::ValueHint::Other)]
+    pub dispatch_timeout: Option<u64>,
+
+    /// Optional number of seconds the whole run may take.
+    /// Once it passes no more bots are dispatched, and any still running are killed.
+    #[arg(long = "run-deadline", global = true, help = "Seconds before the run stops dispatching and kills what's left.", required = false, value_hint = clap::ValueHint::Other)]
+    pub run_deadline: Option<u64>,
+
+    /// Optional number of times each bot is tried in all, failed dispatches are retried until it is reached.
+    /// [default: 1, no retries]
+    #[arg(long = "max-attempts", global = true, value_parser = clap::value_parser!(u32).range(1..), help = "Times each bot is tried before its failure is kept.", required = false, value_hint = clap::ValueHint::Other)]
+    pub max_attempts: Option<u32>,
+
+    /// Optional number of seconds waited before the first retry, doubling (with jitter) on each one after,
+    /// up to a minute. [default: 5]
+    #[arg(long = "retry-backoff", global = true, help = "Seconds waited before the first retry.", required = false, value_hint = clap::ValueHint::Other)]
+    pub retry_backoff: Option<u64>,
+
+    /// Optional `AutomateC` exit codes worth retrying. Can be repeated.
+    /// With neither these nor `--retry-stderr` given, every failure is retried.
+    #[arg(long = "retry-exit-code", global = true, help = "Only retry failures exiting with this code.", required = false, value_hint = clap::ValueHint::Other)]
+    pub retry_exit_codes: Vec<i32>,
+
+    /// Optional regexes matched against a failed dispatch's stderr, case-insensitively, a match is retried.
+    /// Can be repeated.
+    #[arg(long = "retry-stderr", global = true, help = "Only retry failures whose stderr matches this regex.", required = false, value_hint = clap::ValueHint::Other)]
+    pub retry_stderr: Vec<String>,
+
+    /// Optional path to write the detailed dispatch report to once the run has finished.
+    /// The summary table is always printed, this additionally keeps the per-bot output.
+    #[arg(short = 'r', long = "report-file", global = true, help = "The path to write the dispatch report to.", required = false, value_hint = clap::ValueHint::FilePath)]
+    pub report_file: Option<PathBuf>,
+
     /// Optional verbosity level of the logger.
     /// You may provide this as either a string or a number.
     ///
     /// The least verbose as 0 (Error -> Error Only)
     /// Most verbose as 4 (Trace -> Trace Everything
     /// If not provided, the default value is "INFO".
-    #[arg(value_enum, name = "verbosity", short = 'v', long = "verbosity", help = "The verbosity level of the logger.", required = false, default_value = "INFO", value_hint = clap::ValueHint::Other)]
+    #[arg(value_enum, name = "verbosity", short = 'v', long = "verbosity", global = true, help = "The verbosity level of the logger.", required = false, default_value = "INFO", value_hint = clap::ValueHint::Other)]
     pub verbosity_level: Option<VerbosityLevel>,
 
     /// Optional span level of the logger.
@@ -57,8 +290,55 @@ pub struct Cli {
     /// -> "EXIT" (1) - Only log when exiting a span.
     /// -> "ENTER" (2) - Only log when entering a span.
     /// -> "FULL" (3) - Log both entering and exiting a span.
-    #[arg(value_enum, name = "span", short = 's', long = "span", help = "The span level of the logger.", required = false, default_value = "NONE", value_hint = clap::ValueHint::Other)]
+    #[arg(value_enum, name = "span", short = 's', long = "span", global = true, help = "The span level of the logger.", required = false, default_value = "NONE", value_hint = clap::ValueHint::Other)]
     pub span_type: Option<SpanType>,
+
+    /// The format log lines are written in, to the console and the log file.
+    /// If not provided, the default value is "FULL".
+    #[arg(value_enum, name = "log-format", long = "log-format", global = true, help = "The format of the log lines.", required = false, default_value = "FULL", value_hint = clap::ValueHint::Other)]
+    pub log_format: Option<LogFormat>,
+
+    /// Optional path to also write the logs to, rotated daily.
+    /// Each day's file has the date before its extension, e.g. `bulk_runner.2024-10-18.log`.
+    #[arg(long = "log-file", global = true, help = "The path to also write the logs to, rotated daily.", required = false, value_hint = clap::ValueHint::FilePath)]
+    pub log_file: Option<PathBuf>,
+
+    /// The config file that was loaded, if any.
+    #[arg(skip)]
+    loaded_config: Option<PathBuf>,
+
+    /// The name of the profile that was selected from the config file, if any.
+    #[arg(skip)]
+    active_profile: Option<String>,
+
+    /// The values of the selected profile, used wherever a flag wasn't given.
+    #[arg(skip)]
+    profile_values: Profile,
+}
+
+/// What the bulk runner does, every flag applies to each of them.
+///
+/// A bare process name, with no subcommand, is the same as `run`.
+#[derive(Debug, Subcommand, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Dispatch the process on every ready bot.
+    Run {
+        /// The process to run, optional when `--process-map` or `--process-column` maps every bot.
+        #[arg(value_name = "PROCESS", value_hint = clap::ValueHint::Other)]
+        process: Option<String>,
+    },
+    /// Print the dispatch plan without running anything, like `--dry-run`.
+    Plan {
+        /// The process to plan, optional when `--process-map` or `--process-column` maps every bot.
+        #[arg(value_name = "PROCESS", value_hint = clap::ValueHint::Other)]
+        process: Option<String>,
+    },
+    /// Print every bot the query (or bots file) returns, with its parsed status.
+    ListBots,
+    /// Count the resources in each status.
+    Status,
+    /// Check the SQL file returns the name, status and extra columns, without reading any rows.
+    ValidateSql,
 }
 
 /// The verbosity level of the logger.
@@ -80,6 +360,76 @@ pub enum VerbosityLevel {
     Trace,
 }
 
+/// How the SQL Server connection authenticates.
+#[derive(Debug, ValueEnum, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[clap(name = "DbAuthMode", rename_all = "upper")]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DbAuthMode {
+    #[value(name = "INTEGRATED", alias = "integrated", alias = "Integrated")]
+    #[serde(alias = "integrated", alias = "Integrated")]
+    Integrated,
+    #[value(name = "SQL", alias = "sql", alias = "Sql")]
+    #[serde(alias = "sql", alias = "Sql")]
+    Sql,
+}
+
+impl Default for DbAuthMode {
+    #[inline]
+    fn default() -> Self {
+        if cfg!(windows) {
+            DbAuthMode::Integrated
+        } else {
+            DbAuthMode::Sql
+        }
+    }
+}
+
+/// How `AutomateC` signs in to Blue Prism.
+#[derive(Debug, Default, ValueEnum, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[clap(name = "AutomateAuthMode", rename_all = "upper")]
+#[serde(rename_all = "UPPERCASE")]
+pub enum AutomateAuthMode {
+    #[default]
+    #[value(name = "SSO", alias = "sso", alias = "Sso")]
+    #[serde(alias = "sso", alias = "Sso")]
+    Sso,
+    #[value(name = "USER", alias = "user", alias = "User")]
+    #[serde(alias = "user", alias = "User")]
+    User,
+}
+
+/// The format plans, bot listings and run results are printed in.
+#[derive(Debug, Default, ValueEnum, Clone, Copy, PartialEq, Eq)]
+#[clap(name = "OutputFormat", rename_all = "upper")]
+pub enum OutputFormat {
+    #[default]
+    #[value(name = "TABLE", alias = "table", alias = "Table")]
+    Table,
+    #[value(name = "JSON", alias = "json", alias = "Json")]
+    Json,
+    #[value(name = "CSV", alias = "csv", alias = "Csv")]
+    Csv,
+}
+
+/// The format log lines are written in.
+#[derive(Debug, Default, ValueEnum, Clone, Copy, PartialEq, Eq)]
+#[clap(name = "LogFormat", rename_all = "upper")]
+pub enum LogFormat {
+    /// A single line per event with its thread and line number.
+    #[default]
+    #[value(name = "FULL", alias = "full", alias = "Full")]
+    Full,
+    /// Multiple lines per event, for reading by eye.
+    #[value(name = "PRETTY", alias = "pretty", alias = "Pretty")]
+    Pretty,
+    /// A shorter single line per event.
+    #[value(name = "COMPACT", alias = "compact", alias = "Compact")]
+    Compact,
+    /// A JSON object per line, for the log to be parsed.
+    #[value(name = "JSON", alias = "json", alias = "Json")]
+    Json,
+}
+
 #[derive(Debug, ValueEnum, Clone, Copy, PartialEq, Eq)]
 #[clap(name = "SpanType", rename_all = "upper")]
 pub enum SpanType {
@@ -103,7 +453,8 @@ impl Cli {
     /// Create a new instance of the Cli struct.
     ///
     /// # Notes:
-    /// This will check if the `AutomateC` executable exists at the path specified in the prelude.
+    /// This will check if the `AutomateC` executable exists at the configured path (`--automatec-path`),
+    /// or the default path specified in the prelude.
     /// If it does not exist, it will return an error and exit the process.
     ///
     /// There is a bypass for this check, which can be set by setting the environment variable `BYPASS_AUTOMATEC_CHECK`.
@@ -116,56 +467,586 @@ impl Cli {
     }
 
     /// Parses a new `Cli` instance and performs necessary environment checks.
-    /// `check_automate_exists` is checked regardless,
-    /// and `check_db_vars_exist` (on unix systems)
-    /// are called.
+    /// Both `check_automate_exists` and `check_db_vars_exist` are called.
     ///
     /// # Errors
     /// Returns an error if the `AutomateC` executable does not exist at the specified path,
-    /// Returns an error if the necessary DB environment variables are not set (on unix systems).
+    /// Returns an error if the credentials needed by the selected DB auth mode are not set.
     pub fn new_with_checks() -> Result<Self> {
-        let cli = Self::new();
+        let cli = Self::new().load_config()?;
 
-        #[cfg(not(unix))]
-        #[cfg(windows)]
-        let cli = cli.check_automate_exists()?;
+        // Printing the configuration is how a broken one gets debugged, so it must not be blocked by it
+        if cli.print_config() {
+            return Ok(cli);
+        }
 
-        #[cfg(not(windows))]
-        #[cfg(unix)]
         let cli = cli.check_automate_exists()?.check_db_vars_exist()?;
-
         Ok(cli)
     }
 
+    /// Loads the config file (`--config`, or `bulk_runner.toml` in the working directory if present)
+    /// and selects the profile whose values back any flag not given on the command line.
+    ///
+    /// # Errors
+    /// Returns an error if an explicitly given config file is missing or invalid,
+    /// or if the requested profile is not defined in it.
+    pub fn load_config(mut self) -> Result<Self> {
+        let path = match &self.config {
+            Some(path) if !path.is_file() => return Err(Error::ConfigNotFound(path.clone())),
+            Some(path) => Some(path.clone()),
+            None => Some(PathBuf::from(DEFAULT_CONFIG_FILE)).filter(|path| path.is_file()),
+        };
+
+        let Some(path) = path else {
+            if let Some(profile) = &self.profile {
+                return Err(Error::ProfileNotFound(profile.clone()));
+            }
+            return Ok(self);
+        };
+
+        if let Some((name, profile)) = ConfigFile::load(&path)?.select_profile(self.profile.as_deref())? {
+            self.active_profile = Some(name);
+            self.profile_values = profile;
+        }
+        self.loaded_config = Some(path);
+
+        Ok(self)
+    }
+
+    /// The subcommand given, or `run` with the bare process name when there wasn't one.
+    #[must_use]
+    pub fn command(&self) -> Command {
+        self.command.clone().unwrap_or_else(|| {
+            Command::Run {
+                process: self.process.clone(),
+            }
+        })
+    }
+
+    /// The process given to `run` or `plan`, or as the bare positional.
+    #[must_use]
+    #[inline]
+    pub fn process(&self) -> Option<&str> {
+        match &self.command {
+            Some(Command::Run { process } | Command::Plan { process }) => {
+                process.as_deref().or(self.process.as_deref())
+            }
+            _ => self.process.as_deref(),
+        }
+    }
+
     #[must_use]
     #[inline]
-    pub fn process(&self) -> &str {
-        &self.process
+    pub fn process_column(&self) -> Option<&str> {
+        self.process_column
+            .as_deref()
+            .or(self.profile_values.process_column.as_deref())
+    }
+
+    /// The process each bot is dispatched with, built from the process name, `--process-map` and `--process-column`.
+    ///
+    /// # Errors
+    /// Returns an error if none of them were given, or the process map cannot be read.
+    pub fn process_map(&self) -> Result<ProcessMap> {
+        if self.process().is_none() && self.process_map.is_none() && self.process_column().is_none() {
+            return Err(Error::NoProcess);
+        }
+
+        let mapped = match &self.process_map {
+            Some(path) => ProcessMap::load(path)?,
+            None => Vec::new(),
+        };
+
+        Ok(ProcessMap::new(self.process().map(str::to_string))
+            .with_resources(mapped)
+            .with_column(self.process_column().map(str::to_string)))
     }
 
     #[must_use]
     #[inline]
     pub fn concurrency_limit(&self) -> usize {
         self.concurrency_limit
+            .or(self.profile_values.concurrency_limit)
+            .unwrap_or(DEFAULT_CONCURRENCY_LIMIT)
     }
 
     #[must_use]
     #[inline]
-    pub fn limit_total_runnable(&self) -> usize {
+    pub fn limit_total_runnable(&self) -> RunLimit {
         self.limit_total_runnable
+            .or(self.profile_values.limit_total_runnable)
+            .unwrap_or(DEFAULT_LIMIT_TOTAL_RUNNABLE)
     }
 
-    /// Retrieves the SQL file path.
-    ///
-    /// # Panics
-    /// Function will panic if the SQL file path is not set.
+    /// Retrieves the SQL file path, from the flag, the profile, or the default "bots.sql".
     #[must_use]
     #[inline]
-    pub fn sql_file(&self) -> &PathBuf {
-        // self.sql_file.as_ref().unwrap()
+    pub fn sql_file(&self) -> &Path {
         self.sql_file
-            .as_ref()
-            .expect("SQL file path should have a default value")
+            .as_deref()
+            .or(self.profile_values.sql_file.as_deref())
+            .unwrap_or(Path::new(DEFAULT_QUERY_FILE))
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn name_column(&self) -> &str {
+        self.name_column
+            .as_deref()
+            .or(self.profile_values.name_column.as_deref())
+            .unwrap_or(DEFAULT_NAME_COLUMN)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn status_column(&self) -> &str {
+        self.status_column
+            .as_deref()
+            .or(self.profile_values.status_column.as_deref())
+            .unwrap_or(DEFAULT_STATUS_COLUMN)
+    }
+
+    /// The extra columns from the command line, or the profile's when none were given.
+    #[must_use]
+    #[inline]
+    pub fn extra_columns(&self) -> &[String] {
+        match (&self.extra_columns, &self.profile_values.extra_columns) {
+            (flags, Some(profile)) if flags.is_empty() => profile,
+            (flags, _) => flags,
+        }
+    }
+
+    /// The include patterns from the command line, or the profile's when none were given.
+    #[must_use]
+    #[inline]
+    pub fn include(&self) -> &[NamePattern] {
+        match (&self.include, &self.profile_values.include) {
+            (flags, Some(profile)) if flags.is_empty() => profile,
+            (flags, _) => flags,
+        }
+    }
+
+    /// The profile's exclude patterns, followed by any from the command line.
+    #[must_use]
+    pub fn exclude(&self) -> Vec<NamePattern> {
+        self.profile_values
+            .exclude
+            .iter()
+            .flatten()
+            .chain(&self.exclude)
+            .cloned()
+            .collect()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn bot_filter(&self) -> BotFilter {
+        BotFilter::new(self.include().to_vec(), self.exclude())
+    }
+
+    /// The mapping used to read each bot from the query's rows.
+    /// The process column, when given, is read as one of the extra columns.
+    #[must_use]
+    #[inline]
+    pub fn column_map(&self) -> ColumnMap {
+        let process_column = self.process_column().filter(|column| {
+            !self
+                .extra_columns()
+                .iter()
+                .any(|extra| extra.eq_ignore_ascii_case(column))
+        });
+
+        ColumnMap::new(self.name_column(), self.status_column())
+            .with_extras(self.extra_columns())
+            .with_extras(process_column)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn config_file(&self) -> Option<&Path> {
+        self.loaded_config.as_deref()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn profile_name(&self) -> Option<&str> {
+        self.active_profile.as_deref()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn print_config(&self) -> bool {
+        self.print_config
+    }
+
+    /// The configuration this `Cli` resolves to, flags layered over the profile and defaults.
+    #[must_use]
+    #[inline]
+    pub fn resolved_config(&self) -> ResolvedConfig {
+        ResolvedConfig::from(self)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn db_host(&self) -> &str {
+        self.db_host
+            .as_deref()
+            .or(self.profile_values.db_host.as_deref())
+            .unwrap_or(PROD_HOST)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn db_name(&self) -> &str {
+        self.db_name
+            .as_deref()
+            .or(self.profile_values.db_name.as_deref())
+            .unwrap_or(PROD_DB)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn db_port(&self) -> Option<u16> {
+        self.db_port.or(self.profile_values.db_port)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn db_instance(&self) -> Option<&str> {
+        self.db_instance
+            .as_deref()
+            .or(self.profile_values.db_instance.as_deref())
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn db_auth(&self) -> DbAuthMode {
+        self.db_auth.or(self.profile_values.db_auth).unwrap_or_default()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn db_user(&self) -> Option<&str> {
+        self.db_user.as_deref().or(self.profile_values.db_user.as_deref())
+    }
+
+    /// Assembles the `DbInfo` used to connect to the Blue Prism database.
+    ///
+    /// # Errors
+    /// Returns an error if the selected auth mode is missing its credentials,
+    /// or is not supported on this platform.
+    pub fn db_info(&self) -> Result<DbInfo> {
+        let auth = match self.db_auth() {
+            DbAuthMode::Sql => {
+                let user = self.db_user().ok_or(Error::DbEnvVarUserNotSet)?;
+                let password =
+                    bulk_runner_query::sql_password_from_env().map_err(|_| Error::DbEnvVarPasswordNotSet)?;
+                AuthMethod::sql_server(user, password)
+            }
+            #[cfg(windows)]
+            #[cfg(not(unix))]
+            DbAuthMode::Integrated => AuthMethod::Integrated,
+            #[cfg(not(windows))]
+            #[cfg(unix)]
+            DbAuthMode::Integrated => return Err(Error::DbIntegratedAuthUnsupported),
+        };
+
+        let mut db_info = DbInfo::new(self.db_host(), self.db_name(), auth);
+        if let Some(port) = self.db_port() {
+            db_info = db_info.with_port(port);
+        }
+        if let Some(instance) = self.db_instance() {
+            db_info = db_info.with_instance(instance);
+        }
+
+        Ok(db_info)
+    }
+
+    /// Retrieves the configured `AutomateC` executable path,
+    /// falling back to the profile, then `DEFAULT_EXE_PATH` when none was given.
+    #[must_use]
+    #[inline]
+    pub fn automatec_path(&self) -> &Path {
+        self.automatec_path
+            .as_deref()
+            .or(self.profile_values.automatec_path.as_deref())
+            .unwrap_or(&DEFAULT_EXE_PATH)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn automate_auth_mode(&self) -> AutomateAuthMode {
+        self.automate_auth
+            .or(self.profile_values.automate_auth)
+            .unwrap_or_default()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn automate_user(&self) -> Option<&str> {
+        self.automate_user
+            .as_deref()
+            .or(self.profile_values.automate_user.as_deref())
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn automate_password_file(&self) -> Option<&Path> {
+        self.automate_password_file
+            .as_deref()
+            .or(self.profile_values.automate_password_file.as_deref())
+    }
+
+    /// Where the `AutomateC` password is read from: the prompt when asked for, then the password file,
+    /// then the `AUTOMATEC_PASSWORD` environment variable.
+    #[must_use]
+    pub fn password_source(&self) -> PasswordSource {
+        if self.automate_password_prompt {
+            PasswordSource::Prompt
+        } else if let Some(path) = self.automate_password_file() {
+            PasswordSource::File(path.to_path_buf())
+        } else {
+            PasswordSource::Env
+        }
+    }
+
+    /// How `AutomateC` signs in, reading the password from its source for "USER" authentication.
+    ///
+    /// # Errors
+    /// Returns an error if no user was given, or the password can't be read.
+    pub fn automate_auth(&self) -> Result<AutomateAuth> {
+        match self.automate_auth_mode() {
+            AutomateAuthMode::Sso => Ok(AutomateAuth::Sso),
+            AutomateAuthMode::User => {
+                let user = self.automate_user().ok_or(Error::AutomateUserNotSet)?;
+                let password = self.password_source().read(user)?;
+                Ok(AutomateAuth::credentials(user, password))
+            }
+        }
+    }
+
+    /// How `AutomateC` signs in, without reading the password yet.
+    /// "USER" authentication holds a placeholder until the runner reads it from `password_source` to dispatch,
+    /// so plans and dry runs never prompt for it or need it set.
+    ///
+    /// # Errors
+    /// Returns an error if "USER" authentication is used without a user.
+    pub fn planned_automate_auth(&self) -> Result<AutomateAuth> {
+        match self.automate_auth_mode() {
+            AutomateAuthMode::Sso => Ok(AutomateAuth::Sso),
+            AutomateAuthMode::User => {
+                let user = self.automate_user().ok_or(Error::AutomateUserNotSet)?;
+                Ok(AutomateAuth::credentials(user, REDACTED))
+            }
+        }
+    }
+
+    /// Whether only the plan is printed, with `--dry-run` or the `plan` subcommand.
+    #[must_use]
+    #[inline]
+    pub fn dry_run(&self) -> bool {
+        self.dry_run || matches!(self.command, Some(Command::Plan { .. }))
+    }
+
+    /// Whether `AutomateC` will be spawned, only by `run` when it isn't a dry run.
+    #[must_use]
+    #[inline]
+    pub fn dispatches(&self) -> bool {
+        matches!(self.command(), Command::Run { .. }) && !self.dry_run()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn output_format(&self) -> OutputFormat {
+        self.output_format.unwrap_or(OutputFormat::Table)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn output_file(&self) -> Option<&PathBuf> {
+        self.output_file.as_ref()
+    }
+
+    /// The per-dispatch timeout in seconds, as given, from the profile or the default, 0 meaning none.
+    #[must_use]
+    #[inline]
+    pub fn dispatch_timeout_secs(&self) -> u64 {
+        self.dispatch_timeout
+            .or(self.profile_values.dispatch_timeout)
+            .unwrap_or(DEFAULT_DISPATCH_TIMEOUT_SECS)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn dispatch_timeout(&self) -> Option<Duration> {
+        Some(self.dispatch_timeout_secs())
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn run_deadline_secs(&self) -> Option<u64> {
+        self.run_deadline.or(self.profile_values.run_deadline)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn run_deadline(&self) -> Option<Duration> {
+        self.run_deadline_secs().map(Duration::from_secs)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+            .or(self.profile_values.max_attempts)
+            .unwrap_or(1)
+    }
+
+    /// Seconds waited before the first retry, as given, from the profile or the default.
+    #[must_use]
+    #[inline]
+    pub fn retry_backoff_secs(&self) -> u64 {
+        self.retry_backoff
+            .or(self.profile_values.retry_backoff)
+            .unwrap_or(RetryPolicy::DEFAULT_INITIAL_BACKOFF.as_secs())
+    }
+
+    /// The retryable exit codes from the command line, or the profile's when none were given.
+    #[must_use]
+    #[inline]
+    pub fn retry_exit_codes(&self) -> &[i32] {
+        match (&self.retry_exit_codes, &self.profile_values.retry_exit_codes) {
+            (flags, Some(profile)) if flags.is_empty() => profile,
+            (flags, _) => flags,
+        }
+    }
+
+    /// The retryable stderr patterns from the command line, or the profile's when none were given.
+    #[must_use]
+    #[inline]
+    pub fn retry_stderr(&self) -> &[String] {
+        match (&self.retry_stderr, &self.profile_values.retry_stderr) {
+            (flags, Some(profile)) if flags.is_empty() => profile,
+            (flags, _) => flags,
+        }
+    }
+
+    /// The policy deciding which failed dispatches are tried again, and when.
+    ///
+    /// # Errors
+    /// Returns an error if a stderr pattern is not a valid regex.
+    pub fn retry_policy(&self) -> Result<RetryPolicy> {
+        let stderr_patterns = self
+            .retry_stderr()
+            .iter()
+            .map(|pattern| {
+                RegexBuilder::new(pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| Error::InvalidRetryPattern(pattern.clone(), e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(RetryPolicy::new(self.max_attempts())
+            .with_backoff(Duration::from_secs(self.retry_backoff_secs()), RetryPolicy::DEFAULT_MAX_BACKOFF)
+            .with_exit_codes(self.retry_exit_codes().to_vec())
+            .with_stderr_patterns(stderr_patterns))
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn report_file(&self) -> Option<&PathBuf> {
+        self.report_file.as_ref()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn bots_file(&self) -> Option<&Path> {
+        self.bots_file
+            .as_deref()
+            .or(self.profile_values.bots_file.as_deref())
+    }
+
+    /// The resources named with `--resources` and `--resources-file`, in the order given, without duplicates.
+    /// `None` when neither was given, and every bot from the source is eligible.
+    ///
+    /// # Errors
+    /// Returns an error if the resources file cannot be read.
+    pub fn resources(&self) -> Result<Option<Vec<String>>> {
+        if self.resources.is_empty() && self.resources_file.is_none() {
+            return Ok(None);
+        }
+
+        let from_file = match &self.resources_file {
+            Some(path) => std::fs::read_to_string(path).map_err(|e| Error::ResourcesFile(path.clone(), e))?,
+            None => String::new(),
+        };
+
+        let mut resources = Vec::new();
+        for resource in self
+            .resources
+            .iter()
+            .map(String::as_str)
+            .chain(from_file.lines())
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        {
+            if !resources
+                .iter()
+                .any(|seen: &String| seen.eq_ignore_ascii_case(resource))
+            {
+                resources.push(resource.to_string());
+            }
+        }
+
+        Ok(Some(resources))
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn skip_status_check(&self) -> bool {
+        self.skip_status_check
+    }
+
+    /// Where the bots are read from, the bots file when one is given, otherwise the database.
+    /// Narrowed to the named resources when any are given,
+    /// which are taken as idle without reading either when `--skip-status-check` is set.
+    ///
+    /// # Errors
+    /// Returns an error if the resources file cannot be read,
+    /// or reading from the database, but the connection details cannot be assembled.
+    pub fn bot_source(&self) -> Result<Arc<dyn BotSource>> {
+        let resources = self.resources()?;
+
+        if let (Some(resources), true) = (&resources, self.skip_status_check) {
+            let assumed_idle = resources
+                .iter()
+                .map(|resource| BaseBot::from(Bot::new(resource, BotStatusReady::Idle)))
+                .collect();
+            return Ok(Arc::new(StaticBotSource::new(assumed_idle)));
+        }
+
+        let source: Arc<dyn BotSource> = match self.bots_file() {
+            Some(bots_file) => Arc::new(FileBotSource::new(bots_file, self.column_map())),
+            None => Arc::new(self.sql_bot_source()?),
+        };
+
+        Ok(match resources {
+            Some(resources) => Arc::new(NamedBotSource::new(source, resources)),
+            None => source,
+        })
+    }
+
+    /// The bots queried from the database with the SQL file, whether or not a bots file was given.
+    ///
+    /// # Errors
+    /// Returns an error if the SQL file cannot be read, or the connection details cannot be assembled.
+    pub fn sql_bot_source(&self) -> Result<SqlBotSource> {
+        // Read first, so a bad SQL file is reported even when the connection details are missing too
+        let query = self.serialize_sql_file()?;
+        Ok(SqlBotSource::new(self.db_info()?, query, self.column_map()))
     }
 
     #[must_use]
@@ -173,6 +1054,27 @@ impl Cli {
     pub fn verbosity_level(&self) -> VerbosityLevel {
         self.verbosity_level.unwrap_or(VerbosityLevel::Info)
     }
+
+    #[must_use]
+    #[inline]
+    pub fn log_format(&self) -> LogFormat {
+        self.log_format.unwrap_or_default()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn log_file(&self) -> Option<&PathBuf> {
+        self.log_file.as_ref()
+    }
+
+    /// How the run is logged, at the verbosity level and in the log format, to the log file as well when given.
+    #[must_use]
+    #[inline]
+    pub fn logging(&self) -> Logging {
+        Logging::new(self.verbosity_level())
+            .with_format(self.log_format())
+            .with_file(self.log_file().cloned())
+    }
 }
 
 impl Cli {
@@ -185,54 +1087,46 @@ impl Cli {
     /// can also fail if the file is not valid utf-8.
     pub fn serialize_sql_file(&self) -> Result<String> {
         info!("SERIALIZE:: Starting serialization...");
-        let buffer = std::fs::read(self.sql_file())?;
+        let sql_file = self.sql_file();
+        let buffer = std::fs::read(sql_file).map_err(|e| Error::SqlFile(sql_file.to_path_buf(), e))?;
         let sql_file_query = String::from_utf8(buffer)?.replace('\n', " ");
         Ok(sql_file_query)
     }
 
     /// Runs a check to see if the `AutomateC` executable exists at the specified path.
+    /// Skipped for dry-runs and the subcommands that only read, as nothing is spawned.
     ///
     /// # Errors
     /// Returns an error if the `AutomateC` executable does not exist at the specified path.
     #[inline]
     pub fn check_automate_exists(self) -> Result<Self> {
-        if std::env::var("BYPASS_AUTOMATEC_CHECK").is_ok() {
+        if !self.dispatches() || std::env::var("BYPASS_AUTOMATEC_CHECK").is_ok() {
             return Ok(self);
         }
-        let path = std::path::Path::new(&*DEFAULT_EXE_PATH);
-        if !path.exists() {
-            return Err(Error::AutomateCNotFound);
+        let path = self.automatec_path();
+        if !path.is_file() {
+            return Err(Error::AutomateCNotFound(path.to_path_buf()));
         }
         Ok(self)
     }
 
-    /// Linux specific -
-    /// Checks if the necessary DB environment variables are set.
+    /// Checks the selected DB auth mode can be used,
+    /// for "SQL" auth that means the user and password are set.
+    /// Skipped when the bots are read from a file, or the status check is skipped,
+    /// unless the SQL file is being validated.
     ///
     /// # Errors
-    /// Returns an error if the necessary DB environment variables are not set.
-    #[cfg(not(windows))]
-    #[cfg(unix)]
+    /// Returns an error if the necessary DB credentials are not set,
+    /// or the auth mode is not supported on this platform.
     #[inline]
     pub fn check_db_vars_exist(self) -> Result<Self> {
-        let (user, password) =
-            (bulk_runner_query::sql_user_from_env(), bulk_runner_query::sql_password_from_env());
-
-        // Guard clause to manage both || (user | password) not being set
-        if user.is_err() || password.is_err() {
-            // Narrow the error cause to provide feedback on what exactly is missing
-            if let Err(e) = user {
-                error!("DB VAR CHECK:: Failed to get SQL user from env: {}", e);
-                return Err(Error::DbEnvVarUserNotSet);
-            }
-
-            // Narrow the error cause to provide feedback on what exactly is missing
-            if let Err(e) = password {
-                error!("DB VAR CHECK:: Failed to get SQL password from env: {}", e);
-                return Err(Error::DbEnvVarPasswordNotSet);
-            }
-
-            return Err(Error::DbEnvVarsNotSet);
+        let validating = matches!(self.command, Some(Command::ValidateSql));
+        if !validating && (self.bots_file().is_some() || self.skip_status_check) {
+            return Ok(self);
+        }
+        if let Err(e) = self.db_info() {
+            error!("DB VAR CHECK:: {}", e);
+            return Err(e);
         }
 
         Ok(self)
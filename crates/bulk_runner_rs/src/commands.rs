@@ -0,0 +1,263 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use bulk_runner_bots::{Bot, BotStatus};
+use bulk_runner_query::{BotSource, SqlValidation};
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+
+use crate::output::{headers, Render};
+use crate::prelude::*;
+use crate::table::render_table;
+
+/// Every bot read, rendered by `bots_table` or as one record per bot with whether it is available.
+#[derive(Clone, Copy, Debug)]
+pub struct BotListing<'a>(pub &'a [Bot]);
+
+/// The bots read grouped by status, rendered by `status_table` or as one record per status.
+#[derive(Clone, Copy, Debug)]
+pub struct StatusSummary<'a>(pub &'a [Bot]);
+
+/// Reads every bot from `source`, ready or not, in the order they were returned.
+///
+/// No limit is applied, so the listing shows everything a run could choose from.
+///
+/// # Errors
+/// Returns an error if the source can't be read.
+pub async fn read_bots(source: &dyn BotSource) -> Result<Vec<Bot>> {
+    info!("->> {:<12} - {}", "LIST:: Reading bots from", source.describe());
+
+    let mut bots = Vec::new();
+    source
+        .stream_bots(RunLimit::Unlimited, &mut |base_bot| bots.push(Bot::from(base_bot)))
+        .await?;
+    Ok(bots)
+}
+
+/// Renders one line per bot with its parsed status, whether a run would dispatch for it,
+/// and any extra columns read, followed by the totals.
+#[must_use]
+pub fn bots_table(bots: &[Bot]) -> String {
+    let rows = bots
+        .iter()
+        .map(|bot| {
+            [
+                bot.name.clone(),
+                String::from(bot.status.clone()),
+                yes_no(bot.is_available().is_some()),
+                bot.extras
+                    .iter()
+                    .map(|(column, value)| format!("{column}={value}"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let mut table = render_table(["BOT", "STATUS", "AVAILABLE", "EXTRAS"], &rows);
+    let _ = write!(table, "{} bot(s), {} available", bots.len(), available(bots));
+    table
+}
+
+/// Renders the number of resources in each status, and which they are, followed by the totals.
+#[must_use]
+pub fn status_table(bots: &[Bot]) -> String {
+    let rows = by_status(bots)
+        .into_iter()
+        .map(|(status, bots)| {
+            [
+                String::from(status.clone()),
+                bots.len().to_string(),
+                yes_no(bots.iter().any(|bot| bot.is_available().is_some())),
+                bots.iter()
+                    .map(|bot| bot.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let mut table = render_table(["STATUS", "COUNT", "AVAILABLE", "RESOURCES"], &rows);
+    let _ = write!(table, "{} resource(s), {} available", bots.len(), available(bots));
+    table
+}
+
+/// Renders each mapped column and whether the SQL returns it, then every column it does return.
+#[must_use]
+pub fn validation_table(validation: &SqlValidation) -> String {
+    let rows = validation
+        .checks()
+        .iter()
+        .map(|check| {
+            [
+                check.column.clone(),
+                check.role.to_string(),
+                yes_no(check.returned),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let mut table = render_table(["COLUMN", "READ AS", "RETURNED"], &rows);
+    let _ = writeln!(table, "returned: {}", validation.returned().join(", "));
+
+    let missing_extras = validation.missing_extras();
+    if !missing_extras.is_empty() {
+        let _ = writeln!(table, "bots are read without: {}", missing_extras.join(", "));
+    }
+    table.push_str(if validation.is_valid() {
+        "the SQL file is valid"
+    } else {
+        "the SQL file is invalid"
+    });
+    table
+}
+
+impl Serialize for BotListing<'_> {
+    /// Each bot's name, status and extra columns, with whether a run would dispatch for it.
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Listed<'a> {
+            #[serde(flatten)]
+            bot:       &'a Bot,
+            available: bool,
+        }
+
+        serializer.collect_seq(self.0.iter().map(|bot| {
+            Listed {
+                bot,
+                available: bot.is_available().is_some(),
+            }
+        }))
+    }
+}
+
+impl Render for BotListing<'_> {
+    fn table(&self) -> String {
+        bots_table(self.0)
+    }
+
+    /// One record per bot, with a column for every extra column any bot was read with.
+    fn csv_records(&self) -> (Vec<String>, Vec<Vec<String>>) {
+        let extras = self
+            .0
+            .iter()
+            .flat_map(|bot| bot.extras.keys())
+            .collect::<BTreeSet<_>>();
+
+        let records = self
+            .0
+            .iter()
+            .map(|bot| {
+                [
+                    bot.name.clone(),
+                    String::from(bot.status.clone()),
+                    yes_no(bot.is_available().is_some()),
+                ]
+                .into_iter()
+                .chain(
+                    extras
+                        .iter()
+                        .map(|extra| bot.extras.get(*extra).cloned().unwrap_or_default()),
+                )
+                .collect()
+            })
+            .collect();
+
+        let headers = headers(["name", "status", "available"])
+            .into_iter()
+            .chain(extras.into_iter().cloned())
+            .collect();
+        (headers, records)
+    }
+}
+
+impl Serialize for StatusSummary<'_> {
+    /// Each status with its count, whether those bots are available and their names.
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            by_status(self.0)
+                .into_iter()
+                .map(|(status, bots)| StatusGroup(status, bots)),
+        )
+    }
+}
+
+impl Render for StatusSummary<'_> {
+    fn table(&self) -> String {
+        status_table(self.0)
+    }
+
+    /// One record per status, with its resources space separated.
+    fn csv_records(&self) -> (Vec<String>, Vec<Vec<String>>) {
+        let records = by_status(self.0)
+            .into_iter()
+            .map(|(status, bots)| {
+                vec![
+                    String::from(status.clone()),
+                    bots.len().to_string(),
+                    yes_no(bots.iter().any(|bot| bot.is_available().is_some())),
+                    bots.iter()
+                        .map(|bot| bot.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                ]
+            })
+            .collect();
+
+        (headers(["status", "count", "available", "resources"]), records)
+    }
+}
+
+impl Render for SqlValidation {
+    fn table(&self) -> String {
+        validation_table(self)
+    }
+
+    /// One record per mapped column, with whether the SQL returns it.
+    fn csv_records(&self) -> (Vec<String>, Vec<Vec<String>>) {
+        let records = self
+            .checks()
+            .iter()
+            .map(|check| {
+                vec![
+                    check.column.clone(),
+                    check.role.to_string(),
+                    yes_no(check.returned),
+                ]
+            })
+            .collect();
+
+        (headers(["column", "read_as", "returned"]), records)
+    }
+}
+
+/// A status and the bots in it.
+struct StatusGroup<'a>(&'a BotStatus, Vec<&'a Bot>);
+
+impl Serialize for StatusGroup<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let StatusGroup(status, bots) = self;
+        let mut group = serializer.serialize_map(Some(4))?;
+        group.serialize_entry("status", status)?;
+        group.serialize_entry("count", &bots.len())?;
+        group.serialize_entry("available", &bots.iter().any(|bot| bot.is_available().is_some()))?;
+        group.serialize_entry("resources", &bots.iter().map(|bot| &bot.name).collect::<Vec<_>>())?;
+        group.end()
+    }
+}
+
+fn by_status(bots: &[Bot]) -> BTreeMap<&BotStatus, Vec<&Bot>> {
+    let mut by_status = BTreeMap::<&BotStatus, Vec<&Bot>>::new();
+    for bot in bots {
+        by_status.entry(&bot.status).or_default().push(bot);
+    }
+    by_status
+}
+
+fn available(bots: &[Bot]) -> usize {
+    bots.iter().filter(|bot| bot.is_available().is_some()).count()
+}
+
+fn yes_no(yes: bool) -> String {
+    if yes { "yes" } else { "no" }.to_string()
+}
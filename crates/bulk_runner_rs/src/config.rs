@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cli::{AutomateAuthMode, Cli, DbAuthMode};
+use crate::filter::NamePattern;
+use crate::prelude::*;
+use crate::Result;
+
+/// The config file looked for in the current working directory when `--config` isn't given.
+pub static DEFAULT_CONFIG_FILE: &str = "bulk_runner.toml";
+
+/// The on-disk configuration file, holding any number of named environment profiles.
+///
+/// ```toml
+/// default_profile = "uat"
+///
+/// [profiles.uat]
+/// db_host = "UATLGDB1"
+/// db_name = "BP_UAT"
+/// concurrency_limit = 10
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    /// The profile used when `--profile` isn't given.
+    pub default_profile: Option<String>,
+    #[serde(default)]
+    pub profiles:        BTreeMap<String, Profile>,
+}
+
+/// A named set of values for one Blue Prism environment.
+/// Every value is optional, anything left out falls back to the built-in default.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub db_host:                Option<String>,
+    pub db_name:                Option<String>,
+    pub db_port:                Option<u16>,
+    pub db_instance:            Option<String>,
+    pub db_auth:                Option<DbAuthMode>,
+    pub db_user:                Option<String>,
+    pub automatec_path:         Option<PathBuf>,
+    pub automate_auth:          Option<AutomateAuthMode>,
+    pub automate_user:          Option<String>,
+    /// Never the password itself, that is only read from the environment, a file or the prompt.
+    pub automate_password_file: Option<PathBuf>,
+    pub sql_file:               Option<PathBuf>,
+    pub bots_file:              Option<PathBuf>,
+    pub name_column:            Option<String>,
+    pub status_column:          Option<String>,
+    pub extra_columns:          Option<Vec<String>>,
+    pub process_column:         Option<String>,
+    pub include:                Option<Vec<NamePattern>>,
+    pub exclude:                Option<Vec<NamePattern>>,
+    pub concurrency_limit:      Option<usize>,
+    pub limit_total_runnable:   Option<RunLimit>,
+    /// Seconds, 0 for no timeout.
+    pub dispatch_timeout:       Option<u64>,
+    /// Seconds.
+    pub run_deadline:           Option<u64>,
+    pub max_attempts:           Option<u32>,
+    /// Seconds.
+    pub retry_backoff:          Option<u64>,
+    pub retry_exit_codes:       Option<Vec<i32>>,
+    pub retry_stderr:           Option<Vec<String>>,
+}
+
+impl ConfigFile {
+    /// Reads and parses the config file at `path`.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read, or is not a valid config file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        toml::from_str(&contents).map_err(|e| Error::ConfigParse(path.to_path_buf(), e))
+    }
+
+    /// Picks the profile to use, `requested` wins over the file's `default_profile`.
+    ///
+    /// # Errors
+    /// Returns an error if a profile was asked for by name but the file doesn't define it.
+    pub fn select_profile(&self, requested: Option<&str>) -> Result<Option<(String, Profile)>> {
+        let Some(name) = requested.or(self.default_profile.as_deref()) else {
+            return Ok(None);
+        };
+
+        match self.profiles.get(name) {
+            Some(profile) => Ok(Some((name.to_string(), profile.clone()))),
+            None => Err(Error::ProfileNotFound(name.to_string())),
+        }
+    }
+}
+
+/// The configuration a run will actually use, after CLI flags, environment variables,
+/// the selected profile and built-in defaults have been layered (in that order).
+#[derive(Debug, Serialize)]
+pub struct ResolvedConfig {
+    /// Printed as a comment, as it isn't a profile value.
+    #[serde(skip)]
+    pub config_file:            Option<PathBuf>,
+    /// Printed as a comment, as it isn't a profile value.
+    #[serde(skip)]
+    pub profile:                Option<String>,
+    pub db_host:                String,
+    pub db_name:                String,
+    pub db_port:                Option<u16>,
+    pub db_instance:            Option<String>,
+    pub db_auth:                DbAuthMode,
+    pub db_user:                Option<String>,
+    pub automatec_path:         PathBuf,
+    pub automate_auth:          AutomateAuthMode,
+    pub automate_user:          Option<String>,
+    pub automate_password_file: Option<PathBuf>,
+    pub sql_file:               PathBuf,
+    pub bots_file:              Option<PathBuf>,
+    pub name_column:            String,
+    pub status_column:          String,
+    pub extra_columns:          Vec<String>,
+    pub process_column:         Option<String>,
+    pub include:                Vec<NamePattern>,
+    pub exclude:                Vec<NamePattern>,
+    pub concurrency_limit:      usize,
+    pub limit_total_runnable:   RunLimit,
+    pub dispatch_timeout:       u64,
+    pub run_deadline:           Option<u64>,
+    pub max_attempts:           u32,
+    pub retry_backoff:          u64,
+    pub retry_exit_codes:       Vec<i32>,
+    pub retry_stderr:           Vec<String>,
+}
+
+impl From<&Cli> for ResolvedConfig {
+    fn from(cli: &Cli) -> Self {
+        ResolvedConfig {
+            config_file:            cli.config_file().map(Path::to_path_buf),
+            profile:                cli.profile_name().map(str::to_string),
+            db_host:                cli.db_host().to_string(),
+            db_name:                cli.db_name().to_string(),
+            db_port:                cli.db_port(),
+            db_instance:            cli.db_instance().map(str::to_string),
+            db_auth:                cli.db_auth(),
+            db_user:                cli.db_user().map(str::to_string),
+            automatec_path:         cli.automatec_path().to_path_buf(),
+            automate_auth:          cli.automate_auth_mode(),
+            automate_user:          cli.automate_user().map(str::to_string),
+            automate_password_file: cli.automate_password_file().map(Path::to_path_buf),
+            sql_file:               cli.sql_file().to_path_buf(),
+            bots_file:              cli.bots_file().map(Path::to_path_buf),
+            name_column:            cli.name_column().to_string(),
+            status_column:          cli.status_column().to_string(),
+            extra_columns:          cli.extra_columns().to_vec(),
+            process_column:         cli.process_column().map(str::to_string),
+            include:                cli.include().to_vec(),
+            exclude:                cli.exclude(),
+            concurrency_limit:      cli.concurrency_limit(),
+            limit_total_runnable:   cli.limit_total_runnable(),
+            dispatch_timeout:       cli.dispatch_timeout_secs(),
+            run_deadline:           cli.run_deadline_secs(),
+            max_attempts:           cli.max_attempts(),
+            retry_backoff:          cli.retry_backoff_secs(),
+            retry_exit_codes:       cli.retry_exit_codes().to_vec(),
+            retry_stderr:           cli.retry_stderr().to_vec(),
+        }
+    }
+}
+
+impl ResolvedConfig {
+    /// Renders the resolved configuration as TOML, ready to be pasted into a profile.
+    /// The config file and profile it was resolved from are written as comments above the values.
+    ///
+    /// # Errors
+    /// Returns an error if the configuration cannot be serialized.
+    pub fn to_toml(&self) -> Result<String> {
+        let config_file = self
+            .config_file
+            .as_deref()
+            .map_or_else(|| "none".to_string(), |path| path.display().to_string());
+        let profile = self.profile.as_deref().unwrap_or("none");
+
+        Ok(format!("# config file: {config_file}\n# profile: {profile}\n\n{}", toml::to_string_pretty(self)?))
+    }
+}
@@ -0,0 +1,84 @@
+use std::io::{BufRead, IsTerminal};
+
+use crate::prelude::*;
+
+/// The environment variable the `AutomateC` password is read from, unless a file or the prompt is used.
+pub static AUTOMATEC_PASSWORD_ENV: &str = "AUTOMATEC_PASSWORD";
+
+/// Where the `AutomateC` password is read from, it is never taken from the command line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PasswordSource {
+    /// The `AUTOMATEC_PASSWORD` environment variable.
+    Env,
+    /// The first line of a file only its owner can read.
+    File(PathBuf),
+    /// Asked for on the terminal without echoing it, or read as a line from stdin when that isn't a terminal.
+    Prompt,
+}
+
+impl PasswordSource {
+    /// Reads the password for `user`.
+    ///
+    /// # Errors
+    /// Returns an error if the password isn't set or is empty, the file can't be read or is readable by others,
+    /// or the prompt can't be answered.
+    pub fn read(&self, user: &str) -> Result<String> {
+        let password = match self {
+            PasswordSource::Env => {
+                std::env::var(AUTOMATEC_PASSWORD_ENV).map_err(|_| Error::AutomatePasswordNotSet)?
+            }
+            PasswordSource::File(path) => read_password_file(path)?,
+            PasswordSource::Prompt => prompt(user).map_err(Error::AutomatePasswordPrompt)?,
+        };
+
+        let password = password.trim_end_matches(['\r', '\n']).to_string();
+        if password.is_empty() {
+            return Err(match self {
+                PasswordSource::File(path) => {
+                    Error::AutomatePasswordFile(path.clone(), "it is empty".to_string())
+                }
+                _ => Error::AutomatePasswordNotSet,
+            });
+        }
+
+        Ok(password)
+    }
+}
+
+fn read_password_file(path: &Path) -> Result<String> {
+    let unreadable = |e: std::io::Error| Error::AutomatePasswordFile(path.to_path_buf(), e.to_string());
+
+    check_owner_only(&std::fs::metadata(path).map_err(unreadable)?, path)?;
+    let contents = std::fs::read_to_string(path).map_err(unreadable)?;
+
+    Ok(contents.lines().next().unwrap_or_default().to_string())
+}
+
+#[cfg(not(windows))]
+#[cfg(unix)]
+fn check_owner_only(metadata: &std::fs::Metadata, path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = metadata.permissions().mode() & 0o777;
+    if mode & 0o077 != 0 {
+        return Err(Error::AutomatePasswordFileInsecure(path.to_path_buf(), mode));
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+#[cfg(not(unix))]
+fn check_owner_only(_metadata: &std::fs::Metadata, _path: &Path) -> Result<()> {
+    // NTFS access is governed by ACLs rather than mode bits, restricting them is left to the file's owner
+    Ok(())
+}
+
+fn prompt(user: &str) -> std::io::Result<String> {
+    if std::io::stdin().is_terminal() {
+        return rpassword::prompt_password(format!("AutomateC password for {user}: "));
+    }
+
+    let mut password = String::new();
+    std::io::stdin().lock().read_line(&mut password)?;
+    Ok(password)
+}
@@ -3,8 +3,8 @@ pub enum Error {
     #[error("Generic error handler: {0}")]
     Generic(String),
 
-    #[error("The AutomateC binary that is required to run this application couldn't be found!")]
-    AutomateCNotFound,
+    #[error("The AutomateC binary that is required to run this application couldn't be found at: {}", .0.display())]
+    AutomateCNotFound(std::path::PathBuf),
 
     // #[error("AutomateC error: {0}")]
     // AutomateC(#[from] crate::internals::Error),
@@ -27,21 +27,75 @@ pub enum Error {
     #[error("Error parsing file: {0}")]
     Parse(#[from] std::string::FromUtf8Error),
 
+    #[error("JSON serialization error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("CSV serialization error: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("The config file could not be found at: {}", .0.display())]
+    ConfigNotFound(std::path::PathBuf),
+
+    #[error("The config file at {} is invalid: {}", .0.display(), .1)]
+    ConfigParse(std::path::PathBuf, toml::de::Error),
+
+    #[error("Failed to render the configuration: {0}")]
+    ConfigRender(#[from] toml::ser::Error),
+
+    #[error("The profile '{0}' is not defined in the config file")]
+    ProfileNotFound(String),
+
+    #[error("The log file {} could not be created: {}", .0.display(), .1)]
+    LogFile(std::path::PathBuf, String),
+
+    #[error("The resources file {} could not be read: {}", .0.display(), .1)]
+    ResourcesFile(std::path::PathBuf, std::io::Error),
+
+    #[error("The SQL file {} could not be read: {}", .0.display(), .1)]
+    SqlFile(std::path::PathBuf, std::io::Error),
+
+    #[error("The process map {} is invalid: {}", .0.display(), .1)]
+    InvalidProcessMap(std::path::PathBuf, String),
+
+    #[error("No process was given, pass a process name, '--process-map' or '--process-column'")]
+    NoProcess,
+
+    #[error("The SQL file {} doesn't return the {} column(s) bots are read from", .0.display(), .1)]
+    SqlColumnsMissing(std::path::PathBuf, String),
+
+    #[error("Invalid name pattern '{0}': {1}")]
+    InvalidPattern(String, regex::Error),
+
+    #[error("Invalid --retry-stderr pattern '{0}': {1}")]
+    InvalidRetryPattern(String, regex::Error),
+
     #[error("Bulk runner query error in binary: {0}")]
     BulkRunnerQuery(#[from] bulk_runner_query::Error),
 
-    #[cfg(not(windows))]
-    #[cfg(unix)]
-    #[error("One or more required database environment variables are not set")]
-    DbEnvVarsNotSet,
-
-    #[cfg(not(windows))]
-    #[cfg(unix)]
-    #[error("The required 'PROD_SQL_USER' environment variable is not set")]
+    #[error("No SQL user was given, pass '--db-user' or set the 'PROD_SQL_USER' environment variable")]
     DbEnvVarUserNotSet,
 
-    #[cfg(not(windows))]
-    #[cfg(unix)]
     #[error("The required 'PROD_SQL_PASSWORD' environment variable is not set")]
     DbEnvVarPasswordNotSet,
+
+    #[error("No AutomateC user was given, pass '--automate-user' or set the 'AUTOMATEC_USER' environment variable")]
+    AutomateUserNotSet,
+
+    #[error(
+        "No AutomateC password was given, set the 'AUTOMATEC_PASSWORD' environment variable, \
+         or use '--automate-password-file' or '--automate-password-prompt'"
+    )]
+    AutomatePasswordNotSet,
+
+    #[error("The AutomateC password file {} could not be read: {}", .0.display(), .1)]
+    AutomatePasswordFile(std::path::PathBuf, String),
+
+    #[error("The AutomateC password file {} can be read by other users (mode {:o}), restrict it with 'chmod 600'", .0.display(), .1)]
+    AutomatePasswordFileInsecure(std::path::PathBuf, u32),
+
+    #[error("The AutomateC password could not be read from the terminal: {0}")]
+    AutomatePasswordPrompt(std::io::Error),
+
+    #[error("Integrated database authentication is only supported on Windows, use '--db-auth SQL'")]
+    DbIntegratedAuthUnsupported,
 }
@@ -0,0 +1,81 @@
+use std::process::ExitCode;
+
+use crate::runner::RunOutcome;
+use crate::Error;
+
+/// The process exit codes `bulk_runner_rs` finishes with,
+/// so scheduler wrappers can tell a partial run apart from a total failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum RunExitCode {
+    /// Every dispatched bot started (or the dry-run plan was printed).
+    Success = 0,
+    /// The run failed for a reason not covered below (bad arguments, missing `AutomateC`, IO...).
+    Error = 1,
+    /// Some bots started, but at least one did not.
+    PartialFailure = 2,
+    /// Not a single dispatched bot started.
+    TotalFailure = 3,
+    /// The bots could not be queried from the database, or the SQL file doesn't return the columns they are read from.
+    QueryFailure = 4,
+    /// The query ran, but no bots were eligible to be dispatched.
+    NoEligibleBots = 5,
+}
+
+impl From<&RunOutcome> for RunExitCode {
+    fn from(outcome: &RunOutcome) -> Self {
+        match outcome {
+            RunOutcome::Planned(plan) if plan.entries().is_empty() => RunExitCode::NoEligibleBots,
+            RunOutcome::Planned(_) => RunExitCode::Success,
+            RunOutcome::Dispatched(report) => {
+                let unsuccessful = report.failed() + report.timed_out() + report.errored();
+                match (report.len(), unsuccessful) {
+                    (0, _) => RunExitCode::NoEligibleBots,
+                    (_, 0) => RunExitCode::Success,
+                    (total, unsuccessful) if unsuccessful == total => RunExitCode::TotalFailure,
+                    _ => RunExitCode::PartialFailure,
+                }
+            }
+        }
+    }
+}
+
+impl From<&Error> for RunExitCode {
+    fn from(error: &Error) -> Self {
+        match error {
+            Error::Database(_) | Error::Deadpool(_) | Error::SqlColumnsMissing(..) => {
+                RunExitCode::QueryFailure
+            }
+            Error::BulkRunnerQuery(error) if is_query_failure(error) => RunExitCode::QueryFailure,
+            _ => RunExitCode::Error,
+        }
+    }
+}
+
+/// Whether the bots couldn't be queried, the database failing or the query not returning the columns they are read
+/// from, rather than the run failing for another reason.
+fn is_query_failure(error: &bulk_runner_query::Error) -> bool {
+    use bulk_runner_bots::Error as BotsError;
+    use bulk_runner_query::Error as QueryError;
+
+    match error {
+        QueryError::Database(_) | QueryError::Pool(_) | QueryError::Deadpool(_) => true,
+        QueryError::BulkRunnerBots(error) => {
+            matches!(
+                error,
+                BotsError::Database(_)
+                    | BotsError::Deadpool(_)
+                    | BotsError::MissingColumn { .. }
+                    | BotsError::MistypedColumn { .. }
+            )
+        }
+        _ => false,
+    }
+}
+
+impl From<RunExitCode> for ExitCode {
+    #[inline]
+    fn from(code: RunExitCode) -> Self {
+        ExitCode::from(code as u8)
+    }
+}
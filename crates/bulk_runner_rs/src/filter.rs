@@ -0,0 +1,134 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+/// A pattern matched against the whole of a resource name, case-insensitively.
+///
+/// A glob (`*` for any run of characters, `?` for any one) by default, or a regex when prefixed with `re:`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct NamePattern {
+    pattern: String,
+    regex:   Regex,
+}
+
+impl NamePattern {
+    #[must_use]
+    #[inline]
+    pub fn is_match(&self, name: &str) -> bool {
+        self.regex.is_match(name)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+}
+
+impl FromStr for NamePattern {
+    type Err = Error;
+
+    fn from_str(pattern: &str) -> Result<Self> {
+        let expression = if let Some(regex) = pattern.strip_prefix("re:") {
+            format!("^(?:{regex})$")
+        } else {
+            let glob = pattern
+                .split('*')
+                .map(|part| part.split('?').map(regex::escape).collect::<Vec<_>>().join("."))
+                .collect::<Vec<_>>()
+                .join(".*");
+            format!("^{glob}$")
+        };
+
+        let regex = RegexBuilder::new(&expression)
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| Error::InvalidPattern(pattern.to_string(), e))?;
+
+        Ok(NamePattern {
+            pattern: pattern.to_string(),
+            regex,
+        })
+    }
+}
+
+impl TryFrom<String> for NamePattern {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(pattern: String) -> Result<Self> {
+        pattern.parse()
+    }
+}
+
+impl From<NamePattern> for String {
+    #[inline]
+    fn from(pattern: NamePattern) -> Self {
+        pattern.pattern
+    }
+}
+
+impl Display for NamePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.pattern)
+    }
+}
+
+/// Why a bot was kept out of the run.
+#[derive(Clone, Debug)]
+pub enum FilterRule {
+    /// Include patterns were given, and the bot matched none of them.
+    NotIncluded,
+    /// The bot matched this exclude pattern.
+    Excluded(NamePattern),
+}
+
+impl Display for FilterRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterRule::NotIncluded => f.write_str("not matched by any --include"),
+            FilterRule::Excluded(pattern) => write!(f, "--exclude {pattern}"),
+        }
+    }
+}
+
+/// The include and exclude patterns each bot's name is checked against before it is dispatched.
+///
+/// With no include patterns every bot is included, an exclude always wins over an include.
+#[derive(Clone, Debug, Default)]
+pub struct BotFilter {
+    include: Vec<NamePattern>,
+    exclude: Vec<NamePattern>,
+}
+
+impl BotFilter {
+    #[must_use]
+    pub fn new(include: Vec<NamePattern>, exclude: Vec<NamePattern>) -> Self {
+        BotFilter { include, exclude }
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// The rule keeping `name` out of the run, `None` when it may be dispatched.
+    #[must_use]
+    pub fn check(&self, name: &str) -> Option<FilterRule> {
+        if let Some(pattern) = self.exclude.iter().find(|pattern| pattern.is_match(name)) {
+            return Some(FilterRule::Excluded(pattern.clone()));
+        }
+
+        if !self.include.is_empty() && !self.include.iter().any(|pattern| pattern.is_match(name)) {
+            return Some(FilterRule::NotIncluded);
+        }
+
+        None
+    }
+}
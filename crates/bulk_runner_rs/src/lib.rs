@@ -2,12 +2,23 @@
 // pub mod database;
 
 mod error;
+pub mod exit_code;
 pub mod timekeeper;
 
 pub mod cli;
+pub mod commands;
+pub mod config;
+pub mod credentials;
+pub mod filter;
+pub mod logging;
+pub mod output;
 pub mod packets;
+pub mod plan;
 pub mod prelude;
+pub mod process_map;
+pub mod report;
 pub mod runner;
+pub mod table;
 
 pub use std::sync::Arc;
 
@@ -16,20 +27,15 @@ pub use futures::StreamExt;
 pub use tokio::sync::Semaphore;
 pub use tracing::{debug, error, info, warn};
 
+pub use self::exit_code::RunExitCode;
 use self::packets::{Dispatchable, Packet};
 // use bulk_runner_bots::{BaseBot, Bot};
 
 // use bulk_runner_query::{AutomateBuilderBase, AutomateBuilderBaseExt};
 pub use self::prelude::{Error, Result, W};
-pub use self::runner::Runner;
+pub use self::runner::{RunOutcome, Runner};
 pub use crate::timekeeper::TimeKeeper;
 
-pub type TracingSubscriber = tracing_subscriber::fmt::SubscriberBuilder<
-    tracing_subscriber::fmt::format::DefaultFields,
-    tracing_subscriber::fmt::format::Format<tracing_subscriber::fmt::format::Full>,
-    tracing_subscriber::EnvFilter,
->;
-
 // pub use bulk_runner_internals as internals;
 // pub use internals::{AutomateCBuilder, AutomateCFuture};
 
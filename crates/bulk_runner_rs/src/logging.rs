@@ -0,0 +1,133 @@
+use std::io::IsTerminal;
+
+use tracing::Subscriber;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::EnvFilter;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{Layer, Registry};
+
+use crate::cli::{LogFormat, VerbosityLevel};
+use crate::prelude::*;
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// How and where the run is logged.
+///
+/// Logs always go to stderr, keeping stdout for the plan, listing or results,
+/// and to a daily rotated log file as well when one is given.
+#[derive(Debug, Clone)]
+pub struct Logging {
+    level:  VerbosityLevel,
+    format: LogFormat,
+    file:   Option<PathBuf>,
+    ansi:   bool,
+}
+
+impl Logging {
+    /// Logs at `level` in the default format, coloured only when stderr is a terminal.
+    #[must_use]
+    pub fn new(level: VerbosityLevel) -> Self {
+        Logging {
+            level,
+            format: LogFormat::default(),
+            file: None,
+            ansi: std::io::stderr().is_terminal(),
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn with_format(mut self, format: LogFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Also writes the logs to `file`, a new one each day with the date before its extension.
+    #[must_use]
+    #[inline]
+    pub fn with_file(mut self, file: Option<PathBuf>) -> Self {
+        self.file = file;
+        self
+    }
+
+    /// Whether the console logs are coloured, the log file never is.
+    #[must_use]
+    #[inline]
+    pub fn with_ansi(mut self, ansi: bool) -> Self {
+        self.ansi = ansi;
+        self
+    }
+
+    /// Builds the subscriber, and the guard flushing the log file once dropped.
+    ///
+    /// The guard must be held until the run has finished, or the last lines may never reach the file.
+    ///
+    /// # Errors
+    /// Returns an error if the log file cannot be created.
+    pub fn build(&self) -> Result<(impl Subscriber + Send + Sync + 'static, Option<WorkerGuard>)> {
+        let mut layers = vec![self.layer(std::io::stderr, self.ansi)];
+
+        let guard = match &self.file {
+            Some(file) => {
+                let (writer, guard) = tracing_appender::non_blocking(daily_appender(file)?);
+                layers.push(self.layer(writer, false));
+                Some(guard)
+            }
+            None => None,
+        };
+
+        let subscriber = Registry::default().with(layers).with(EnvFilter::from(self.level));
+        Ok((subscriber, guard))
+    }
+
+    /// Builds the subscriber and sets it as the global default.
+    ///
+    /// # Errors
+    /// Returns an error if the log file cannot be created, or a global subscriber was already set.
+    pub fn init(&self) -> Result<Option<WorkerGuard>> {
+        let (subscriber, guard) = self.build()?;
+        tracing::subscriber::set_global_default(subscriber).map_err(|e| Error::Generic(e.to_string()))?;
+        Ok(guard)
+    }
+
+    fn layer<W>(&self, writer: W, ansi: bool) -> BoxedLayer
+    where
+        W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+    {
+        let layer = tracing_subscriber::fmt::layer()
+            .with_writer(writer)
+            .with_ansi(ansi)
+            .with_level(true)
+            .with_line_number(true)
+            .with_thread_ids(true);
+
+        match self.format {
+            LogFormat::Full => layer.boxed(),
+            LogFormat::Pretty => layer.pretty().boxed(),
+            LogFormat::Compact => layer.compact().boxed(),
+            LogFormat::Json => layer.with_ansi(false).json().boxed(),
+        }
+    }
+}
+
+/// Rotates `file` daily, `logs/bulk_runner.log` being written as `logs/bulk_runner.2024-10-18.log` and so on.
+fn daily_appender(file: &Path) -> Result<RollingFileAppender> {
+    let directory = file
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let prefix = file.file_stem().unwrap_or(file.as_os_str()).to_string_lossy();
+
+    let mut builder = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(prefix);
+    if let Some(extension) = file.extension() {
+        builder = builder.filename_suffix(extension.to_string_lossy());
+    }
+
+    builder
+        .build(directory)
+        .map_err(|e| Error::LogFile(file.to_path_buf(), e.to_string()))
+}
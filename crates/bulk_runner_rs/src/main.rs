@@ -1,33 +1,100 @@
-use bulk_runner_rs::{cli, error, info, Runner, TimeKeeper, TracingSubscriber};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use bulk_runner_rs::cli::Command;
+use bulk_runner_rs::{cli, commands, error, info, output, RunExitCode, RunOutcome, Runner, TimeKeeper};
 pub use bulk_runner_rs::{Error, Result, W};
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
     let timekeep = TimeKeeper::default();
-    let cli = cli::Cli::new_with_checks()?;
-    init_logger(cli.verbosity_level().into()).init();
+    let cli = match cli::Cli::new_with_checks() {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return RunExitCode::from(&e).into();
+        }
+    };
+
+    if cli.print_config() {
+        return match cli.resolved_config().to_toml() {
+            Ok(config) => {
+                println!("{config}");
+                RunExitCode::Success.into()
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                RunExitCode::from(&e).into()
+            }
+        };
+    }
+
+    // Held until exit, so the last lines are flushed to the log file
+    let _log_guard = match cli.logging().init() {
+        Ok(guard) => guard,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return RunExitCode::from(&e).into();
+        }
+    };
 
     info!("->> {:<12}", "MAIN:: 1. Starting bulk_runner_rs... ");
     info!("->> {:<12}", "MAIN:: 2. Cli initialized... ");
 
-    if let Err(e) = Runner::from(cli).run().await {
-        error!("->> {:<12} - {}", "MAIN:: 3. Error running cli... ", e);
-        std::process::exit(1);
-    }
+    let exit_code = match run(cli).await {
+        Ok(exit_code) => exit_code,
+        Err(e) => {
+            error!("->> {:<12} - {}", "MAIN:: 3. Error running cli... ", e);
+            RunExitCode::from(&e)
+        }
+    };
 
     timekeep.print_elapsed();
     timekeep.print_started_at();
+    info!("->> {:<12} - {:?}", "MAIN:: 4. Exiting with", exit_code);
 
-    Ok(())
+    exit_code.into()
 }
 
-fn init_logger(level: tracing_subscriber::filter::EnvFilter) -> TracingSubscriber {
-    tracing_subscriber::fmt()
-        .with_level(true)
-        .with_ansi(true)
-        .with_line_number(true)
-        .with_thread_ids(true)
-        .with_env_filter(level)
-    // .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
-    // .with_timer(tracing_subscriber::fmt::time::SystemTime)
+async fn run(cli: cli::Cli) -> Result<RunExitCode> {
+    match cli.command() {
+        Command::Run { .. } | Command::Plan { .. } => {
+            let outcome: RunOutcome = Runner::try_from(cli)?.run().await?;
+            Ok(RunExitCode::from(&outcome))
+        }
+        Command::ListBots => {
+            let bots = commands::read_bots(&*cli.bot_source()?).await?;
+            write_output(&cli, &commands::BotListing(&bots))?;
+            Ok(found(&bots))
+        }
+        Command::Status => {
+            let bots = commands::read_bots(&*cli.bot_source()?).await?;
+            write_output(&cli, &commands::StatusSummary(&bots))?;
+            Ok(found(&bots))
+        }
+        Command::ValidateSql => {
+            let validation = cli.sql_bot_source()?.validate().await?;
+            write_output(&cli, &validation)?;
+            if !validation.is_valid() {
+                let missing = validation.missing_required().join(", ");
+                return Err(Error::SqlColumnsMissing(cli.sql_file().to_path_buf(), missing));
+            }
+            Ok(RunExitCode::Success)
+        }
+    }
+}
+
+/// Prints `value` in the `--output` format, or writes it to the `--output-file`.
+fn write_output(cli: &cli::Cli, value: &impl output::Render) -> Result<()> {
+    let rendered = output::render(value, cli.output_format())?;
+    output::write_output(&rendered, cli.output_file().map(PathBuf::as_path))
+}
+
+/// Listing no bots at all is reported like a run with none eligible.
+fn found(bots: &[bulk_runner_bots::Bot]) -> RunExitCode {
+    if bots.is_empty() {
+        RunExitCode::NoEligibleBots
+    } else {
+        RunExitCode::Success
+    }
 }
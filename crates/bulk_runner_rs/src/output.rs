@@ -0,0 +1,65 @@
+use serde::Serialize;
+
+use crate::cli::OutputFormat;
+use crate::prelude::*;
+
+/// What the tool reports, a plan, a bot listing or the results of a run,
+/// printable as the table a person reads or as JSON or CSV for automation to parse.
+pub trait Render: Serialize {
+    /// The plain-text table printed by default.
+    fn table(&self) -> String;
+
+    /// The CSV header and one record per line, flattened so each bot is a single record.
+    fn csv_records(&self) -> (Vec<String>, Vec<Vec<String>>);
+}
+
+/// Renders `value` in the requested format.
+///
+/// # Errors
+/// Returns an error if the value cannot be serialized to JSON or CSV.
+pub fn render<T: Render>(value: &T, format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Table => Ok(value.table()),
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(value)?),
+        OutputFormat::Csv => {
+            let (headers, records) = value.csv_records();
+            render_csv(&headers, &records)
+        }
+    }
+}
+
+/// Renders the header and records as CSV, quoting any field that needs it.
+///
+/// # Errors
+/// Returns an error if a record cannot be written.
+pub fn render_csv(headers: &[String], records: &[Vec<String>]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(headers)?;
+    for record in records {
+        writer.write_record(record)?;
+    }
+
+    let csv = writer.into_inner().map_err(csv::IntoInnerError::into_error)?;
+    Ok(String::from_utf8(csv)?)
+}
+
+/// Prints the rendered output to stdout, or writes it to `path` replacing any existing file.
+///
+/// # Errors
+/// Returns an error if the file cannot be written.
+pub fn write_output(rendered: &str, path: Option<&Path>) -> Result<()> {
+    let rendered = rendered.trim_end();
+    match path {
+        Some(path) => {
+            info!("->> {:<12} - {}", "OUTPUT:: Writing output", path.display());
+            std::fs::write(path, format!("{rendered}\n"))?;
+        }
+        None => println!("{rendered}"),
+    }
+    Ok(())
+}
+
+/// Headers from static column names.
+pub(crate) fn headers<const N: usize>(columns: [&str; N]) -> Vec<String> {
+    columns.map(str::to_string).to_vec()
+}
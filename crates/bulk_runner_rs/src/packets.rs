@@ -1,27 +1,36 @@
 use bulk_runner_bots::{Bot, BotStatus, BotStatusReady};
+use serde::Serialize;
+
+impl From<Packet> for (Bot, String) {
+    fn from(packet: Packet) -> Self {
+        (packet.bot, packet.process_name)
+    }
+}
 
 impl From<Dispatchable> for Vec<(Bot, String)> {
     fn from(dispatchable: Dispatchable) -> Self {
-        dispatchable
-            .bots
-            .into_iter()
-            .map(|packet| (packet.bot, packet.process_name))
-            .collect()
+        dispatchable.bots.into_iter().map(<(Bot, String)>::from).collect()
     }
 }
 
+#[derive(Serialize)]
 pub struct Packet {
     pub bot:          Bot,
     pub process_name: String,
 }
 
+#[derive(Serialize)]
 pub struct Dispatchable {
     pub bots: Vec<Packet>,
 }
 
 impl Packet {
+    /// Pairs the bot with its process, a logged out bot is promoted to idle as `AutomateC` will log it in.
     #[must_use]
-    pub fn new(bot: Bot, process_name: String) -> Self {
+    pub fn new(mut bot: Bot, process_name: String) -> Self {
+        if bot.is_logged_out() {
+            bot.status = BotStatus::Ready(BotStatusReady::Idle);
+        }
         Packet { bot, process_name }
     }
 }
@@ -39,17 +48,7 @@ where
 
 impl FromIterator<Packet> for Dispatchable {
     fn from_iter<T: IntoIterator<Item = Packet>>(iter: T) -> Self {
-        let bots = iter
-            .into_iter()
-            .map(|mut packet| {
-                if packet.bot.is_logged_out() {
-                    packet.bot.status = BotStatus::Ready(BotStatusReady::Idle);
-                    packet
-                } else {
-                    packet
-                }
-            })
-            .collect::<Vec<Packet>>();
+        let bots = iter.into_iter().collect::<Vec<Packet>>();
 
         Dispatchable { bots }
     }
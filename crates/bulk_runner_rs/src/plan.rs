@@ -0,0 +1,143 @@
+use std::fmt::{self, Display};
+use std::path::Path;
+
+use bulk_runner_query::{AutomateAuth, FilteredBot};
+use serde::Serialize;
+
+use crate::output::{headers, Render};
+use crate::packets::{Dispatchable, Packet};
+use crate::prelude::*;
+use crate::report::filtered_table;
+use crate::table::render_table;
+
+/// A single `AutomateC` invocation that would be made for a bot.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanEntry {
+    pub bot:     String,
+    pub status:  String,
+    pub process: String,
+    pub argv:    Vec<String>,
+}
+
+/// Everything a run would dispatch, without having dispatched any of it,
+/// and the bots that would be filtered out.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Plan {
+    entries:  Vec<PlanEntry>,
+    filtered: Vec<FilteredBot>,
+}
+
+impl PlanEntry {
+    /// Builds the entry for a single packet, `exe_path` becomes the first element of the argv.
+    /// Any password in the argv is redacted.
+    ///
+    /// # Errors
+    /// Returns an error if the arguments aren't a command `AutomateC` would accept.
+    pub fn new(packet: &Packet, exe_path: &Path, auth: &AutomateAuth) -> Result<Self> {
+        let commander = bulk_runner_query::build_commander(&packet.bot, &packet.process_name, auth)?;
+
+        let argv = std::iter::once(exe_path.display().to_string())
+            .chain(commander.redacted())
+            .collect();
+
+        Ok(PlanEntry {
+            bot: packet.bot.name.clone(),
+            status: String::from(packet.bot.status.clone()),
+            process: packet.process_name.clone(),
+            argv,
+        })
+    }
+}
+
+impl Plan {
+    /// Plans an entry for each packet.
+    ///
+    /// # Errors
+    /// Returns an error if any bot's arguments aren't a command `AutomateC` would accept.
+    pub fn new(dispatchable: &Dispatchable, exe_path: &Path, auth: &AutomateAuth) -> Result<Self> {
+        let entries = dispatchable
+            .bots
+            .iter()
+            .map(|packet| PlanEntry::new(packet, exe_path, auth))
+            .collect::<Result<_>>()?;
+
+        Ok(Plan {
+            entries,
+            filtered: Vec::new(),
+        })
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn with_filtered(mut self, filtered: Vec<FilteredBot>) -> Self {
+        self.filtered = filtered;
+        self
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn entries(&self) -> &[PlanEntry] {
+        &self.entries
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn filtered(&self) -> &[FilteredBot] {
+        &self.filtered
+    }
+}
+
+impl Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows = self
+            .entries
+            .iter()
+            .map(|entry| {
+                [
+                    entry.bot.clone(),
+                    entry.status.clone(),
+                    entry.process.clone(),
+                    entry.argv.join(" "),
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        f.write_str(&render_table(["BOT", "STATUS", "PROCESS", "ARGV"], &rows))?;
+        write!(f, "{} bot(s) planned", rows.len())?;
+
+        if !self.filtered.is_empty() {
+            write!(f, "\n\n{}", filtered_table(&self.filtered))?;
+        }
+        Ok(())
+    }
+}
+
+impl Render for Plan {
+    fn table(&self) -> String {
+        self.to_string()
+    }
+
+    /// One record per planned bot, then one per filtered bot with the rule that filtered it.
+    fn csv_records(&self) -> (Vec<String>, Vec<Vec<String>>) {
+        let planned = self.entries.iter().map(|entry| {
+            vec![
+                entry.bot.clone(),
+                entry.status.clone(),
+                entry.process.clone(),
+                entry.argv.join(" "),
+                String::new(),
+            ]
+        });
+        let filtered = self.filtered.iter().map(|bot| {
+            vec![
+                bot.bot_name.clone(),
+                bot.status.clone(),
+                String::new(),
+                String::new(),
+                bot.rule.clone(),
+            ]
+        });
+
+        (headers(["bot", "status", "process", "argv", "filtered_by"]), planned.chain(filtered).collect())
+    }
+}
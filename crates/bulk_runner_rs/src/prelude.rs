@@ -1,5 +1,5 @@
 // in-crate Error type
-pub use std::path::PathBuf;
+pub use std::path::{Path, PathBuf};
 pub use std::sync::Arc;
 
 pub use tracing::{debug, error, info, warn};
@@ -13,8 +13,15 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub struct W<T>(pub T);
 
 pub static DEFAULT_QUERY_FILE: &str = "bots.sql";
+pub static DEFAULT_CONCURRENCY_LIMIT: usize = 30;
+pub static DEFAULT_LIMIT_TOTAL_RUNNABLE: RunLimit = RunLimit::Limited(30);
+pub static DEFAULT_NAME_COLUMN: &str = "name";
+pub static DEFAULT_STATUS_COLUMN: &str = "status";
+/// Seconds a single `AutomateC` dispatch may run before it is killed.
+pub static DEFAULT_DISPATCH_TIMEOUT_SECS: u64 = 300;
 
 pub static PROD_HOST: &str = "PRDLGDB2";
 pub static PROD_DB: &str = "BP_PRD";
 
 pub use bulk_runner_bots::DEFAULT_EXE_PATH;
+pub use bulk_runner_query::RunLimit;
@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+
+use bulk_runner_bots::Bot;
+
+use crate::prelude::*;
+
+/// Which process each bot is dispatched with.
+///
+/// A bot's process is looked up in the mapping file first, then in its process column,
+/// and falls back to the default process. A bot with none of them isn't dispatched.
+#[derive(Clone, Debug, Default)]
+pub struct ProcessMap {
+    default:     Option<String>,
+    /// Keyed by uppercased resource name.
+    by_resource: BTreeMap<String, String>,
+    column:      Option<String>,
+}
+
+impl ProcessMap {
+    /// Every bot gets `default`, until resources or a column are mapped.
+    #[must_use]
+    pub fn new(default: Option<String>) -> Self {
+        ProcessMap {
+            default,
+            ..ProcessMap::default()
+        }
+    }
+
+    /// Maps each `(resource, process)` pair, resources are matched case-insensitively.
+    #[must_use]
+    pub fn with_resources<I, R, P>(mut self, resources: I) -> Self
+    where
+        I: IntoIterator<Item = (R, P)>,
+        R: AsRef<str>,
+        P: Into<String>,
+    {
+        self.by_resource.extend(
+            resources
+                .into_iter()
+                .map(|(resource, process)| (resource.as_ref().to_uppercase(), process.into())),
+        );
+        self
+    }
+
+    /// Reads each bot's process from the extra column `column`, when the source returned one for it.
+    #[must_use]
+    #[inline]
+    pub fn with_column(mut self, column: Option<String>) -> Self {
+        self.column = column;
+        self
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn default_process(&self) -> Option<&str> {
+        self.default.as_deref()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn column(&self) -> Option<&str> {
+        self.column.as_deref()
+    }
+
+    /// The process `bot` is dispatched with, `None` when nothing maps it and there is no default.
+    #[must_use]
+    pub fn process_for<'a>(&'a self, bot: &'a Bot) -> Option<&'a str> {
+        self.by_resource
+            .get(&bot.name.to_uppercase())
+            .map(String::as_str)
+            .or_else(|| {
+                self.column
+                    .as_deref()
+                    .and_then(|column| bot.extra(column))
+                    .map(str::trim)
+                    .filter(|process| !process.is_empty())
+            })
+            .or(self.default.as_deref())
+    }
+
+    /// Reads a mapping file of resources to processes.
+    ///
+    /// A `.csv` file has a header row with `resource` and `process` columns,
+    /// a `.toml` file maps each process name to the list of resources it runs on.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed, or maps a resource to more than one process.
+    pub fn load(path: &Path) -> Result<Vec<(String, String)>> {
+        let contents = std::fs::read_to_string(path)?;
+        let invalid = |reason: String| Error::InvalidProcessMap(path.to_path_buf(), reason);
+
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_lowercase);
+
+        let pairs = match extension.as_deref() {
+            Some("csv") => read_csv(&contents).map_err(invalid)?,
+            Some("toml") => {
+                toml::from_str::<BTreeMap<String, Vec<String>>>(&contents)
+                    .map_err(|e| invalid(e.to_string()))?
+                    .into_iter()
+                    .flat_map(|(process, resources)| {
+                        resources
+                            .into_iter()
+                            .map(move |resource| (resource.trim().to_string(), process.clone()))
+                    })
+                    .collect()
+            }
+            _ => return Err(invalid("expected a .csv or .toml file".to_string())),
+        };
+
+        let mut seen = BTreeMap::new();
+        for (resource, process) in &pairs {
+            if let Some(previous) = seen.insert(resource.to_uppercase(), process) {
+                if previous != process {
+                    return Err(invalid(format!(
+                        "{resource} is mapped to both '{previous}' and '{process}'"
+                    )));
+                }
+            }
+        }
+
+        Ok(pairs)
+    }
+}
+
+impl From<&str> for ProcessMap {
+    #[inline]
+    fn from(process: &str) -> Self {
+        ProcessMap::new(Some(process.to_string()))
+    }
+}
+
+impl From<String> for ProcessMap {
+    #[inline]
+    fn from(process: String) -> Self {
+        ProcessMap::new(Some(process))
+    }
+}
+
+fn read_csv(contents: &str) -> std::result::Result<Vec<(String, String)>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(contents.as_bytes());
+
+    let headers = reader.headers().map_err(|e| e.to_string())?.clone();
+    let position = |column: &str| {
+        headers
+            .iter()
+            .position(|header| header.eq_ignore_ascii_case(column))
+            .ok_or_else(|| {
+                format!("no '{column}' column, found: {}", headers.iter().collect::<Vec<_>>().join(", "))
+            })
+    };
+    let (resource_at, process_at) = (position("resource")?, position("process")?);
+
+    let mut pairs = Vec::new();
+    for (line, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| e.to_string())?;
+        match (record.get(resource_at), record.get(process_at)) {
+            (Some(resource), Some(process)) if !resource.is_empty() && !process.is_empty() => {
+                pairs.push((resource.to_string(), process.to_string()));
+            }
+            _ => return Err(format!("record {} needs both a resource and a process", line + 1)),
+        }
+    }
+
+    Ok(pairs)
+}
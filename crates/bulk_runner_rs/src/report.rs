@@ -0,0 +1,192 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use bulk_runner_query::{DispatchRecord, DispatchReport, FilteredBot};
+
+use crate::output::{headers, Render};
+use crate::table::render_table;
+use crate::Result;
+
+/// Renders the one-line-per-bot summary of a run, with the session started or why it failed,
+/// followed by the totals,
+/// then any bots that were filtered out and the rule that filtered them.
+#[must_use]
+pub fn summary_table(report: &DispatchReport) -> String {
+    let rows = report.records().iter().map(summary_row).collect::<Vec<_>>();
+
+    let mut summary = render_table(
+        [
+            "BOT",
+            "PROCESS",
+            "EXIT",
+            "TRIES",
+            "STARTED",
+            "DURATION",
+            "OUTCOME",
+            "SESSION / REASON",
+        ],
+        &rows,
+    );
+    summary.push_str(&totals_line(report));
+
+    if !report.filtered().is_empty() {
+        summary.push_str("\n\n");
+        summary.push_str(&filtered_table(report.filtered()));
+    }
+    summary
+}
+
+/// Renders the bots kept out of a run, with the rule that filtered each, followed by their count.
+#[must_use]
+pub fn filtered_table(filtered: &[FilteredBot]) -> String {
+    let rows = filtered
+        .iter()
+        .map(|bot| [bot.bot_name.clone(), bot.status.clone(), bot.rule.clone()])
+        .collect::<Vec<_>>();
+
+    let mut table = render_table(["BOT", "STATUS", "FILTERED BY"], &rows);
+    let _ = write!(table, "{} bot(s) filtered out", rows.len());
+    table
+}
+
+/// Renders the summary table, then the argv and captured output of every dispatch,
+/// with any attempts that were retried before it.
+#[must_use]
+pub fn detailed_report(report: &DispatchReport) -> String {
+    let mut detailed = summary_table(report);
+
+    for record in report.records() {
+        let _ = writeln!(detailed, "\n== {} ({}) ==", record.bot_name, record.outcome_label());
+        let _ = writeln!(detailed, "argv:     {}", record.argv.join(" "));
+        let _ = writeln!(detailed, "started:  {}", record.started_at.format("%Y-%m-%d %H:%M:%S%.3f"));
+        let _ = writeln!(detailed, "finished: {}", record.finished_at.format("%Y-%m-%d %H:%M:%S%.3f"));
+        for (attempt, retried) in record.retried.iter().enumerate() {
+            let _ = writeln!(
+                detailed,
+                "attempt {}: {} at {} after {:.2?}, retried in {:.2?}",
+                attempt + 1,
+                retried.outcome_label(),
+                retried.started_at.format("%H:%M:%S%.3f"),
+                retried.duration,
+                retried.backoff
+            );
+            if let Some(output) = retried.outcome.output() {
+                let _ = writeln!(detailed, "  status: {}", output.status());
+                if let Some(reason) = output.failure_reason() {
+                    let _ = writeln!(detailed, "  reason: {reason}");
+                }
+                let _ = writeln!(detailed, "  stderr: {}", output.stderr().trim_end());
+            }
+        }
+        if let Some(session_id) = record.session_id() {
+            let _ = writeln!(detailed, "session:  {session_id}");
+        }
+        if let Some(reason) = record.failure_reason() {
+            let _ = writeln!(detailed, "reason:   {reason}");
+        }
+        if let Some(output) = record.output() {
+            let _ = writeln!(detailed, "status:   {}", output.status());
+            let _ = writeln!(detailed, "stdout:\n{}", output.stdout().trim_end());
+            let _ = writeln!(detailed, "stderr:\n{}", output.stderr().trim_end());
+        }
+    }
+
+    detailed
+}
+
+/// Writes the detailed report to `path`, replacing any existing file.
+///
+/// # Errors
+/// Returns an error if the file cannot be written.
+pub fn write_report(report: &DispatchReport, path: &Path) -> Result<()> {
+    std::fs::write(path, detailed_report(report))?;
+    Ok(())
+}
+
+impl Render for DispatchReport {
+    fn table(&self) -> String {
+        summary_table(self)
+    }
+
+    /// One record per dispatched bot, then one per filtered bot with a "FILTERED" outcome.
+    fn csv_records(&self) -> (Vec<String>, Vec<Vec<String>>) {
+        let dispatched = self.records().iter().map(|record| {
+            vec![
+                record.bot_name.clone(),
+                record.process.clone(),
+                record.outcome_kind().to_string(),
+                record
+                    .exit_code()
+                    .map(|code| code.to_string())
+                    .unwrap_or_default(),
+                record.attempts().to_string(),
+                record.started_at.to_rfc3339(),
+                record.finished_at.to_rfc3339(),
+                record.duration.as_millis().to_string(),
+                record.session_id().map(str::to_string).unwrap_or_default(),
+                record
+                    .failure_reason()
+                    .map(ToString::to_string)
+                    .unwrap_or_default(),
+                String::new(),
+            ]
+        });
+        let filtered = self.filtered().iter().map(|bot| {
+            let mut record = vec![String::new(); 11];
+            record[0].clone_from(&bot.bot_name);
+            record[2] = "FILTERED".to_string();
+            record[10].clone_from(&bot.rule);
+            record
+        });
+
+        (
+            headers([
+                "bot",
+                "process",
+                "outcome",
+                "exit_code",
+                "attempts",
+                "started_at",
+                "finished_at",
+                "duration_ms",
+                "session_id",
+                "failure_reason",
+                "filtered_by",
+            ]),
+            dispatched.chain(filtered).collect(),
+        )
+    }
+}
+
+fn summary_row(record: &DispatchRecord) -> [String; 8] {
+    [
+        record.bot_name.clone(),
+        record.process.clone(),
+        record
+            .exit_code()
+            .map_or_else(|| "-".to_string(), |code| code.to_string()),
+        record.attempts().to_string(),
+        record.started_at.format("%H:%M:%S").to_string(),
+        format!("{:.2?}", record.duration),
+        record.outcome_label(),
+        record
+            .session_id()
+            .map(str::to_string)
+            .or_else(|| record.failure_reason().map(ToString::to_string))
+            .unwrap_or_else(|| "-".to_string()),
+    ]
+}
+
+fn totals_line(report: &DispatchReport) -> String {
+    format!(
+        "{} dispatched: {} started, {} failed, {} timed out, {} skipped, {} errored, {} retried, {} filtered out",
+        report.len(),
+        report.succeeded(),
+        report.failed(),
+        report.timed_out(),
+        report.skipped(),
+        report.errored(),
+        report.retried(),
+        report.filtered().len()
+    )
+}
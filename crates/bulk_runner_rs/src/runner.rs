@@ -1,83 +1,431 @@
-use futures::StreamExt;
+use std::time::Duration;
 
-use crate::cli::Cli;
+use bulk_runner_bots::{AutomateCExecutor, Bot, DispatchExecutor};
+use bulk_runner_query::{
+    AutomateAuth,
+    BotSource,
+    DispatchReport,
+    DispatchTimeouts,
+    FilteredBot,
+    RetryPolicy,
+};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::task::JoinHandle;
+
+use crate::cli::{Cli, OutputFormat};
+use crate::credentials::PasswordSource;
+use crate::filter::BotFilter;
+use crate::plan::Plan;
 use crate::prelude::*;
-use crate::{Dispatchable, Packet};
+use crate::process_map::ProcessMap;
+use crate::{output, report, Dispatchable, Packet};
+
+/// The `(Bot, process)` pairs ready for dispatch, and the pipeline producing them.
+type Pipeline = (UnboundedReceiver<(Bot, String)>, JoinHandle<Result<Vec<FilteredBot>>>);
 
 pub struct Runner {
-    process:              String,
+    process:              ProcessMap,
     concurrency_limit:    usize,
-    limit_total_runnable: usize,
-    sql_file_contents:    String,
+    limit_total_runnable: RunLimit,
+    source:               Arc<dyn BotSource>,
+    filter:               BotFilter,
+    executor:             Option<Arc<dyn DispatchExecutor>>,
+    dispatch_timeout:     Option<Duration>,
+    run_deadline:         Option<Duration>,
+    retry:                RetryPolicy,
+    auth:                 AutomateAuth,
+    password_source:      Option<PasswordSource>,
+    dry_run:              bool,
+    output_format:        OutputFormat,
+    output_file:          Option<PathBuf>,
+    report_file:          Option<PathBuf>,
+    automatec_path:       PathBuf,
+}
+
+/// What a finished `Runner::run` produced.
+#[derive(Debug)]
+pub enum RunOutcome {
+    /// A dry-run, nothing was dispatched.
+    Planned(Plan),
+    /// Bots were handed to the executor, one record per bot.
+    Dispatched(DispatchReport),
 }
 
-impl From<Cli> for Runner {
+impl TryFrom<Cli> for Runner {
+    type Error = Error;
+
+    /// Builds the runner from the parsed `Cli`.
+    ///
+    /// # Errors
+    /// Fails if no process was given, the SQL file, process map or resources file cannot be read, a retry pattern is invalid,
+    /// "USER" authentication is used without a user,
+    /// or the database connection details cannot be assembled from the `Cli`.
+    /// The `AutomateC` password is only read once the run dispatches.
     #[inline]
-    fn from(cli: Cli) -> Self {
-        Runner {
-            process:              cli.process().to_string(),
-            concurrency_limit:    cli.concurrency_limit(),
-            limit_total_runnable: cli.limit_total_runnable(),
-            sql_file_contents:    cli.serialize_sql_file().unwrap_or("bots.sql".to_string()),
-        }
+    fn try_from(cli: Cli) -> Result<Self> {
+        Ok(Runner::new(cli.process_map()?, cli.bot_source()?)
+            .with_concurrency_limit(cli.concurrency_limit())
+            .with_limit_total_runnable(cli.limit_total_runnable())
+            .with_filter(cli.bot_filter())
+            .with_automatec_path(cli.automatec_path())
+            .with_dispatch_timeout(cli.dispatch_timeout())
+            .with_run_deadline(cli.run_deadline())
+            .with_retry_policy(cli.retry_policy()?)
+            .with_auth(cli.planned_automate_auth()?)
+            .with_password_source(cli.password_source())
+            .with_dry_run(cli.dry_run())
+            .with_output_format(cli.output_format())
+            .with_output_file(cli.output_file().cloned())
+            .with_report_file(cli.report_file().cloned()))
     }
 }
 
 impl Runner {
-    // TODO: may be better to atually return an Option<()> here?
+    /// A runner for `process` (a single process name, or a `ProcessMap`) on the bots read from `source`,
+    /// with every other setting at its default.
+    #[must_use]
+    pub fn new(process: impl Into<ProcessMap>, source: Arc<dyn BotSource>) -> Self {
+        Runner {
+            process: process.into(),
+            concurrency_limit: DEFAULT_CONCURRENCY_LIMIT,
+            limit_total_runnable: DEFAULT_LIMIT_TOTAL_RUNNABLE,
+            source,
+            filter: BotFilter::default(),
+            executor: None,
+            dispatch_timeout: Some(Duration::from_secs(DEFAULT_DISPATCH_TIMEOUT_SECS)),
+            run_deadline: None,
+            retry: RetryPolicy::default(),
+            auth: AutomateAuth::default(),
+            password_source: None,
+            dry_run: false,
+            output_format: OutputFormat::default(),
+            output_file: None,
+            report_file: None,
+            automatec_path: DEFAULT_EXE_PATH.to_path_buf(),
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn with_concurrency_limit(mut self, concurrency_limit: usize) -> Self {
+        self.concurrency_limit = concurrency_limit;
+        self
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn with_limit_total_runnable(mut self, limit_total_runnable: RunLimit) -> Self {
+        self.limit_total_runnable = limit_total_runnable;
+        self
+    }
+
+    /// Keeps bots whose names don't pass `filter` out of the run, they are listed in the plan and report instead.
+    #[must_use]
+    #[inline]
+    pub fn with_filter(mut self, filter: BotFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Points the runner at the `AutomateC` executable, spawned for each bot unless `with_executor` swapped it out.
+    #[must_use]
+    #[inline]
+    pub fn with_automatec_path(mut self, automatec_path: impl Into<PathBuf>) -> Self {
+        self.automatec_path = automatec_path.into();
+        self
+    }
+
+    /// How long a single dispatch may run before it is killed, `None` to let it run for as long as it takes.
+    #[must_use]
+    #[inline]
+    pub fn with_dispatch_timeout(mut self, dispatch_timeout: Option<Duration>) -> Self {
+        self.dispatch_timeout = dispatch_timeout;
+        self
+    }
+
+    /// How long the whole run may take, counted from `run()`.
+    #[must_use]
+    #[inline]
+    pub fn with_run_deadline(mut self, run_deadline: Option<Duration>) -> Self {
+        self.run_deadline = run_deadline;
+        self
+    }
+
+    /// Which failed dispatches are tried again and when, by default each bot is tried once.
+    #[must_use]
+    #[inline]
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// How `AutomateC` signs in for every dispatch, by default with `/sso`.
+    #[must_use]
+    #[inline]
+    pub fn with_auth(mut self, auth: AutomateAuth) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Reads the `AutomateC` password from `password_source` when the run dispatches,
+    /// in place of the one the "USER" `with_auth` holds. Plans never read it.
+    #[must_use]
+    #[inline]
+    pub fn with_password_source(mut self, password_source: PasswordSource) -> Self {
+        self.password_source = Some(password_source);
+        self
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// The format the plan or run results are printed in, by default a table.
+    #[must_use]
+    #[inline]
+    pub fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
+    /// Writes the plan or run results to `output_file` instead of stdout.
+    #[must_use]
+    #[inline]
+    pub fn with_output_file(mut self, output_file: Option<PathBuf>) -> Self {
+        self.output_file = output_file;
+        self
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn with_report_file(mut self, report_file: Option<PathBuf>) -> Self {
+        self.report_file = report_file;
+        self
+    }
+
+    /// Swaps the executor used to dispatch bots, by default `AutomateC` is spawned for each bot.
+    /// It is kept whichever order this and `with_automatec_path` are called in.
+    #[must_use]
+    #[inline]
+    pub fn with_executor(mut self, executor: Arc<dyn DispatchExecutor>) -> Self {
+        self.executor = Some(executor);
+        self
+    }
 
     /// Orchestrates the entire bulk runner process.
     ///
+    /// Bots are dispatched as they stream in from the query, rather than once it has finished.
+    /// When the runner is in dry-run mode, the plan is printed instead and nothing is dispatched.
+    /// Otherwise the summary of the dispatch report is printed, and written to the report file if one was given.
+    /// Either is printed in the output format, to the output file when one was given.
+    ///
     /// # Errors
     /// Can fail if any step in the process encounters an error.
     /// We do our best-effort to recover, and failing that we log the error and continue.
-    pub async fn run(&self) -> Result<()> {
+    pub async fn run(&self) -> Result<RunOutcome> {
         info!("->> {:<12}", "RUN:: Starting run");
 
-        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        if self.dry_run {
+            info!("->> {:<12}", "RUN:: Dry run, printing plan...");
+            let plan = self.plan().await?;
+            self.write_output(&plan)?;
+            return Ok(RunOutcome::Planned(plan));
+        }
+
+        let auth = self.dispatch_auth()?;
+        let timeouts = DispatchTimeouts::unbounded()
+            .with_per_dispatch(self.dispatch_timeout)
+            .with_run_deadline(self.run_deadline);
+        let (packet_rx, mut pipeline_handle) = self.spawn_pipeline();
+
+        let report = bulk_runner_query::stream_dispatch(
+            packet_rx,
+            self.concurrency_limit,
+            self.executor(),
+            timeouts,
+            self.retry.clone(),
+            auth,
+        )
+        .await;
+
+        // A query still going at the deadline is abandoned, the bots it already returned are reported
+        let pipeline = match timeouts.deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline, &mut pipeline_handle).await.ok(),
+            None => Some((&mut pipeline_handle).await),
+        };
+        let pipeline = if let Some(pipeline) = pipeline {
+            pipeline?
+        } else {
+            warn!(
+                "->> {:<12} - {}",
+                "RUN:: Deadline", "Run deadline passed while still reading bots, abandoning the query"
+            );
+            pipeline_handle.abort();
+            Ok(Vec::new())
+        };
+
+        // Bots dispatched before a mid-stream query failure are still reported, the failure is returned after
+        let (filtered, pipeline) = match pipeline {
+            Ok(filtered) => (filtered, Ok(())),
+            Err(e) => (Vec::new(), Err(e)),
+        };
+        let report = report.with_filtered(filtered);
+
+        self.write_output(&report)?;
+
+        if let Some(report_file) = &self.report_file {
+            info!("->> {:<12} - {}", "RUN:: Writing report", report_file.display());
+            report::write_report(&report, report_file)?;
+        }
+
+        pipeline?;
+
+        Ok(RunOutcome::Dispatched(report))
+    }
+
+    /// How `AutomateC` signs in to dispatch, with the password read from its source when one was given.
+    fn dispatch_auth(&self) -> Result<AutomateAuth> {
+        match (&self.auth, &self.password_source) {
+            (AutomateAuth::Credentials { user, .. }, Some(source)) => {
+                Ok(AutomateAuth::credentials(user, source.read(user)?))
+            }
+            (auth, _) => Ok(auth.clone()),
+        }
+    }
+
+    /// The executor swapped in with `with_executor`, otherwise one spawning `AutomateC` from the runner's path.
+    fn executor(&self) -> Arc<dyn DispatchExecutor> {
+        match &self.executor {
+            Some(executor) => executor.clone(),
+            None => Arc::new(AutomateCExecutor::new(&self.automatec_path)),
+        }
+    }
+
+    /// Prints `value` in the output format, or writes it to the output file.
+    fn write_output(&self, value: &impl output::Render) -> Result<()> {
+        let rendered = output::render(value, self.output_format)?;
+        output::write_output(&rendered, self.output_file.as_deref())
+    }
+
+    /// Runs the query and builds the plan of what would be dispatched, without dispatching it.
+    ///
+    /// # Errors
+    /// Can fail if querying the bots fails, or a bot's command isn't one `AutomateC` would accept.
+    pub async fn plan(&self) -> Result<Plan> {
+        let (dispatchable, filtered) = self.collect_dispatchable().await?;
+        Ok(Plan::new(&dispatchable, &self.automatec_path, &self.auth)?.with_filtered(filtered))
+    }
+
+    /// Drains the pipeline into packets, for when every bot is needed up front.
+    /// Returned alongside the bots that were filtered out.
+    async fn collect_dispatchable(&self) -> Result<(Dispatchable, Vec<FilteredBot>)> {
+        let (mut packet_rx, pipeline_handle) = self.spawn_pipeline();
+
+        let mut packets = Vec::with_capacity(self.concurrency_limit);
+        while let Some((bot, process_name)) = packet_rx.recv().await {
+            packets.push(Packet::new(bot, process_name));
+        }
+
+        let filtered = pipeline_handle.await??;
+
+        Ok((packets.into_iter().collect::<Dispatchable>(), filtered))
+    }
 
-        // Serialize the sql file to a string
-        let sql_file_contents = self.sql_file_contents.clone();
+    /// Spawns the query, and a task turning each available bot it streams back into a
+    /// `(Bot, process)` pair, with logged out bots promoted to idle.
+    /// Bots that aren't available, or that the filter rejects, are set aside rather than sent on.
+    ///
+    /// Only the pairs sent on count toward the run limit, so set aside bots never use it up,
+    /// and once it is reached the rest are left out.
+    ///
+    /// The pairs arrive on the returned receiver as soon as their rows are read,
+    /// the handle resolves to the filtered out bots, or the query's failure, once the stream has ended.
+    fn spawn_pipeline(&self) -> Pipeline {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let (packet_tx, packet_rx) = tokio::sync::mpsc::unbounded_channel();
 
         let limit_total_runnable = self.limit_total_runnable;
+        let source = self.source.clone();
 
-        // Spawn a task to fetch the bots from the database,
+        // Spawn a task to fetch the bots from the source,
         let query_handle = tokio::spawn(async move {
-            // let tx = tx.clone();
-            info!("->> {:<12}", "RUN::  Querying database...");
-            bulk_runner_query::query_database(tx, sql_file_contents, limit_total_runnable).await;
+            info!("->> {:<12}", "RUN::  Querying bot source...");
+            bulk_runner_query::query_database(tx, source, limit_total_runnable).await
         });
 
-        // As the query runs, it will return back a Bot (which will have been filled already, we need the Bot to go to next step)
-        let capacity = self.concurrency_limit;
+        // As the query runs, it will return back a Bot (which will have been filled already),
+        // each one is handed on to dispatch straight away
+        let processes = self.process.clone();
+        let filter = self.filter.clone();
 
-        let future_bots = tokio::spawn(async move {
-            let mut bots = Vec::with_capacity(capacity);
+        let pipeline_handle = tokio::spawn(async move {
+            let mut filtered = Vec::new();
+            let mut sent = 0;
+            let mut over_limit = 0;
             while let Some(bot) = rx.recv().await {
                 if bot.is_available().is_none() {
-                    warn!("->> {:<12} - {:?}", "Future Bots:: Bot not available...", &bot);
-                    break;
+                    info!(
+                        "->> {:<12} - {} - {}",
+                        "Pipeline:: Bot not available",
+                        &bot.name,
+                        String::from(bot.status.clone())
+                    );
+                    filtered.push(FilteredBot {
+                        bot_name: bot.name.clone(),
+                        status:   String::from(bot.status.clone()),
+                        rule:     "not available".to_string(),
+                    });
+                    continue;
+                }
+
+                if let Some(rule) = filter.check(&bot.name) {
+                    info!("->> {:<12} - {} - {}", "Pipeline:: Bot filtered out", &bot.name, rule);
+                    filtered.push(FilteredBot {
+                        bot_name: bot.name.clone(),
+                        status:   String::from(bot.status.clone()),
+                        rule:     rule.to_string(),
+                    });
+                    continue;
                 }
 
-                info!("->> {:<12} - {:?}", "Future Bots:: Bot received...", &bot);
-                bots.push(bot.into_future());
+                let Some(process_name) = processes.process_for(&bot).map(str::to_string) else {
+                    warn!("->> {:<12} - {}", "Pipeline:: No process mapped", &bot.name);
+                    filtered.push(FilteredBot {
+                        bot_name: bot.name.clone(),
+                        status:   String::from(bot.status.clone()),
+                        rule:     "no process mapped".to_string(),
+                    });
+                    continue;
+                };
+
+                if !limit_total_runnable.allows(sent + 1) {
+                    over_limit += 1;
+                    continue;
+                }
+
+                info!("->> {:<12} - {:?}", "Pipeline:: Bot received...", &bot);
+                sent += 1;
+                packet_tx
+                    .send(Packet::new(bot, process_name).into())
+                    .unwrap_or_default();
             }
             rx.close();
-            bots
-        })
-        .await?;
+            drop(packet_tx);
 
-        let dispatchable: Dispatchable = futures::future::join_all(future_bots)
-            .await
-            .into_iter()
-            .filter_map(|bot| bot.0)
-            .map(|bot| Packet::new(bot, self.process.clone()))
-            .collect::<Dispatchable>();
-
-        query_handle.await?;
+            if over_limit > 0 {
+                warn!(
+                    "->> {:<12} - {} more ready bots were left out, the limit is {}",
+                    "Pipeline:: Limit", over_limit, limit_total_runnable
+                );
+            }
 
-        bulk_runner_query::cli_dispatch(dispatchable.into(), self.concurrency_limit).await;
+            query_handle.await??;
+            Ok(filtered)
+        });
 
-        Ok(())
+        (packet_rx, pipeline_handle)
     }
 }
@@ -0,0 +1,28 @@
+use std::fmt::Write;
+
+/// Renders rows as a left-aligned, space-padded plain-text table with a header line.
+#[must_use]
+pub fn render_table<const N: usize>(headers: [&str; N], rows: &[[String; N]]) -> String {
+    let mut widths = headers.map(str::len);
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let mut table = String::new();
+    write_row(&mut table, &headers, &widths);
+    for row in rows {
+        write_row(&mut table, &row.each_ref().map(String::as_str), &widths);
+    }
+    table
+}
+
+fn write_row<const N: usize>(table: &mut String, cells: &[&str; N], widths: &[usize; N]) {
+    let mut line = String::new();
+    for (cell, width) in cells.iter().zip(widths) {
+        let _ = write!(line, "{cell:<width$}  ");
+    }
+    table.push_str(line.trim_end());
+    table.push('\n');
+}
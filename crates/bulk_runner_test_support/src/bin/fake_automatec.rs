@@ -0,0 +1,155 @@
+//! Stands in for `AutomateC.exe` in tests.
+//!
+//! Accepts `/sso`, `/run <process>`, `/resource <name>`, `/user <name>` and `/password <password>`
+//! (case-insensitively, like `AutomateC`), then answers as scripted in the `fake_automatec.json` next
+//! to the executable, a resource scripted with several attempts gets the next one each time it is run.
+//! Unknown switches, a missing `/run` or `/resource`, or mixed/missing credentials
+//! exit with 1, as `AutomateC` would.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bulk_runner_test_support::{FakeScript, Invocation, LOG_FILE, SCRIPT_FILE};
+
+#[derive(Default)]
+struct Args {
+    sso:      bool,
+    process:  Option<String>,
+    resource: Option<String>,
+    user:     Option<String>,
+    password: Option<String>,
+}
+
+fn main() -> ExitCode {
+    let raw = std::env::args().skip(1).collect::<Vec<_>>();
+    let started_ms = now_ms();
+
+    let exit_code = match parse(&raw).and_then(validate) {
+        Ok(args) => respond(&args),
+        Err(message) => {
+            eprintln!("{message}");
+            1
+        }
+    };
+
+    let args = parse(&raw).unwrap_or_default();
+    log(&Invocation {
+        args: raw,
+        process: args.process,
+        resource: args.resource,
+        user: args.user,
+        sso: args.sso,
+        exit_code,
+        started_ms,
+        finished_ms: now_ms(),
+    });
+
+    ExitCode::from(u8::try_from(exit_code & 0xff).unwrap_or(1))
+}
+
+fn parse(raw: &[String]) -> Result<Args, String> {
+    let mut args = Args::default();
+    let mut raw = raw.iter();
+
+    while let Some(switch) = raw.next() {
+        let mut value = || {
+            raw.next()
+                .cloned()
+                .ok_or_else(|| format!("Missing value for switch: {switch}"))
+        };
+
+        match switch.to_lowercase().as_str() {
+            "/sso" => args.sso = true,
+            "/run" => args.process = Some(value()?),
+            "/resource" => args.resource = Some(value()?),
+            "/user" => args.user = Some(value()?),
+            "/password" => args.password = Some(value()?),
+            _ => return Err(format!("Unrecognised switch: {switch}")),
+        }
+    }
+
+    Ok(args)
+}
+
+fn validate(args: Args) -> Result<Args, String> {
+    if args.process.is_none() {
+        return Err("No process given, use /run <process>".to_string());
+    }
+    if args.resource.is_none() {
+        return Err("No resource given, use /resource <name>".to_string());
+    }
+
+    match (args.sso, &args.user, &args.password) {
+        (true, None, None) | (false, Some(_), Some(_)) => Ok(args),
+        (true, _, _) => Err("/sso cannot be combined with /user or /password".to_string()),
+        (false, _, _) => Err("No credentials given, use /sso or /user and /password".to_string()),
+    }
+}
+
+fn respond(args: &Args) -> i32 {
+    let resource = args.resource.as_deref().unwrap_or_default();
+    let process = args.process.as_deref().unwrap_or_default();
+
+    let script = std::fs::read(beside_exe(SCRIPT_FILE))
+        .ok()
+        .and_then(|script| serde_json::from_slice::<FakeScript>(&script).ok())
+        .unwrap_or_default();
+    let response = script.response_for_attempt(resource, previous_runs(resource));
+
+    std::thread::sleep(Duration::from_millis(response.delay_ms));
+
+    match &response.stdout {
+        Some(stdout) => print!("{stdout}"),
+        None => println!("Started process '{process}' on resource '{resource}'"),
+    }
+    eprint!("{}", response.stderr);
+
+    response.exit_code
+}
+
+fn log(invocation: &Invocation) {
+    let Ok(mut line) = serde_json::to_string(invocation) else {
+        return;
+    };
+    line.push('\n');
+
+    // One write per line, so concurrent runs appending to the same log don't interleave
+    if let Ok(mut file) = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(beside_exe(LOG_FILE))
+    {
+        let _ = file.write_all(line.as_bytes());
+    }
+}
+
+/// How many times `resource` has been run before, going by the log.
+fn previous_runs(resource: &str) -> usize {
+    std::fs::read_to_string(beside_exe(LOG_FILE))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Invocation>(line).ok())
+        .filter(|run| {
+            run.resource
+                .as_deref()
+                .is_some_and(|run_on| run_on.eq_ignore_ascii_case(resource))
+        })
+        .count()
+}
+
+fn beside_exe(file_name: &str) -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(file_name)))
+        .unwrap_or_else(|| PathBuf::from(file_name))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| u64::try_from(since.as_millis()).unwrap_or(u64::MAX))
+        .unwrap_or_default()
+}
@@ -0,0 +1,228 @@
+//! Test support for `bulk_runner_rs`: a fake `AutomateC` binary, and helpers for driving it from tests.
+//!
+//! The `fake_automatec` binary accepts the same switches as `AutomateC` (`/sso`, `/run`, `/resource`,
+//! `/user`, `/password`), and answers each resource as scripted in a `FakeScript`.
+//! Every invocation is appended to a log, so tests can check what was run, when, and how many at once.
+//!
+//! `FakeAutomateC::install` copies the binary into its own temporary directory alongside its script,
+//! which keeps concurrently running tests from seeing each other's scripts or invocations.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{fs, io};
+
+use serde::{Deserialize, Serialize};
+use tempfile::TempDir;
+
+/// The script file the fake reads, looked for next to its own executable.
+pub const SCRIPT_FILE: &str = "fake_automatec.json";
+
+/// The log the fake appends one JSON line to per invocation, next to its own executable.
+pub const LOG_FILE: &str = "fake_automatec.log";
+
+/// How the fake answers a single invocation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FakeResponse {
+    pub exit_code: i32,
+    pub delay_ms:  u64,
+    /// Written to stdout, when `None` a message naming the process and resource is written instead.
+    pub stdout:    Option<String>,
+    pub stderr:    String,
+}
+
+impl Default for FakeResponse {
+    #[inline]
+    fn default() -> Self {
+        FakeResponse::started()
+    }
+}
+
+impl FakeResponse {
+    /// The process was started, exit code 0.
+    #[must_use]
+    pub fn started() -> Self {
+        FakeResponse {
+            exit_code: 0,
+            delay_ms:  0,
+            stdout:    None,
+            stderr:    String::new(),
+        }
+    }
+
+    /// `AutomateC` failed with `exit_code`.
+    #[must_use]
+    pub fn failed(exit_code: i32) -> Self {
+        FakeResponse {
+            exit_code,
+            ..FakeResponse::started()
+        }
+    }
+
+    #[must_use]
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay_ms = u64::try_from(delay.as_millis()).unwrap_or(u64::MAX);
+        self
+    }
+
+    #[must_use]
+    pub fn with_stdout(mut self, stdout: impl Into<String>) -> Self {
+        self.stdout = Some(stdout.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_stderr(mut self, stderr: impl Into<String>) -> Self {
+        self.stderr = stderr.into();
+        self
+    }
+}
+
+/// How the fake answers each resource, anything not listed gets the `fallback`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FakeScript {
+    pub fallback:  FakeResponse,
+    /// Keyed by uppercased resource name.
+    pub resources: BTreeMap<String, FakeResponse>,
+    /// One response per invocation for a resource, the last repeating once they run out.
+    /// Keyed by uppercased resource name, and checked before `resources`.
+    #[serde(default)]
+    pub attempts:  BTreeMap<String, Vec<FakeResponse>>,
+}
+
+impl FakeScript {
+    #[must_use]
+    pub fn new(fallback: FakeResponse) -> Self {
+        FakeScript {
+            fallback,
+            resources: BTreeMap::new(),
+            attempts: BTreeMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_resource(mut self, resource: impl AsRef<str>, response: FakeResponse) -> Self {
+        self.resources.insert(resource.as_ref().to_uppercase(), response);
+        self
+    }
+
+    /// Answers the invocations for `resource` with `responses` in turn, to script a flaky resource.
+    #[must_use]
+    pub fn with_attempts(mut self, resource: impl AsRef<str>, responses: Vec<FakeResponse>) -> Self {
+        self.attempts.insert(resource.as_ref().to_uppercase(), responses);
+        self
+    }
+
+    /// The response for `resource`, matched case-insensitively.
+    #[must_use]
+    pub fn response_for(&self, resource: &str) -> &FakeResponse {
+        self.response_for_attempt(resource, 0)
+    }
+
+    /// The response for `resource` once it has already been run `previous` times.
+    #[must_use]
+    pub fn response_for_attempt(&self, resource: &str, previous: usize) -> &FakeResponse {
+        let resource = resource.to_uppercase();
+
+        self.attempts
+            .get(&resource)
+            .and_then(|responses| responses.get(previous).or(responses.last()))
+            .or_else(|| self.resources.get(&resource))
+            .unwrap_or(&self.fallback)
+    }
+}
+
+/// One run of the fake, as recorded in its log.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Invocation {
+    pub args:        Vec<String>,
+    pub process:     Option<String>,
+    pub resource:    Option<String>,
+    pub user:        Option<String>,
+    pub sso:         bool,
+    pub exit_code:   i32,
+    /// Milliseconds since the unix epoch.
+    pub started_ms:  u64,
+    /// Milliseconds since the unix epoch.
+    pub finished_ms: u64,
+}
+
+/// A copy of the fake binary in its own directory, with its own script and log.
+pub struct FakeAutomateC {
+    dir: TempDir,
+    exe: PathBuf,
+}
+
+impl FakeAutomateC {
+    /// Copies the `fake_exe` binary into a fresh temporary directory, and writes `script` beside it.
+    ///
+    /// Integration tests get `fake_exe` from `env!("CARGO_BIN_EXE_fake_automatec")`.
+    ///
+    /// # Errors
+    /// Returns an error if the directory, binary copy or script cannot be written.
+    pub fn install(fake_exe: impl AsRef<Path>, script: &FakeScript) -> io::Result<Self> {
+        let dir = tempfile::tempdir()?;
+        let exe = dir.path().join(
+            fake_exe
+                .as_ref()
+                .file_name()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "fake_exe has no file name"))?,
+        );
+
+        fs::copy(fake_exe, &exe)?;
+        fs::write(dir.path().join(SCRIPT_FILE), serde_json::to_vec_pretty(script)?)?;
+
+        Ok(FakeAutomateC { dir, exe })
+    }
+
+    /// The path to hand to the runner as its `AutomateC` executable.
+    #[must_use]
+    #[inline]
+    pub fn exe_path(&self) -> &Path {
+        &self.exe
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn dir(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Every invocation so far, in the order they finished.
+    ///
+    /// # Errors
+    /// Returns an error if the log exists but cannot be read or parsed.
+    pub fn invocations(&self) -> io::Result<Vec<Invocation>> {
+        let log = match fs::read_to_string(self.dir.path().join(LOG_FILE)) {
+            Ok(log) => log,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        log.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(io::Error::from))
+            .collect()
+    }
+}
+
+/// The most invocations that were running at the same time.
+#[must_use]
+pub fn max_concurrency(invocations: &[Invocation]) -> usize {
+    let mut edges = invocations
+        .iter()
+        .flat_map(|run| [(run.started_ms, 1_i64), (run.finished_ms, -1_i64)])
+        .collect::<Vec<_>>();
+
+    // At the same instant, a finish is counted before a start, touching runs didn't overlap
+    edges.sort_unstable();
+
+    let mut running = 0_i64;
+    let mut most = 0_i64;
+    for (_, change) in edges {
+        running += change;
+        most = most.max(running);
+    }
+
+    usize::try_from(most).unwrap_or_default()
+}
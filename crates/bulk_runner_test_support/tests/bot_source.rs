@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use bulk_runner_bots::{Bot, BotStatusNotReady, BotStatusReady, ColumnMap};
+use bulk_runner_query::{BotSource, FileBotSource, NamedBotSource, RunLimit, StaticBotSource};
+use bulk_runner_rs::cli::Cli;
+use bulk_runner_rs::{RunExitCode, RunOutcome, Runner};
+use bulk_runner_test_support::{FakeAutomateC, FakeScript};
+use clap::Parser;
+
+const FAKE_AUTOMATEC: &str = env!("CARGO_BIN_EXE_fake_automatec");
+const PROCESS: &str = "Morning Startup";
+
+async fn run_from_file(fake: &FakeAutomateC, file_name: &str, contents: &str) -> RunOutcome {
+    let path = fake.dir().join(file_name);
+    std::fs::write(&path, contents).unwrap();
+
+    Runner::new(PROCESS, Arc::new(FileBotSource::new(path, ColumnMap::default())))
+        .with_automatec_path(fake.exe_path())
+        .with_limit_total_runnable(RunLimit::Unlimited)
+        .run()
+        .await
+        .unwrap()
+}
+
+fn dispatched(outcome: &RunOutcome) -> Vec<&str> {
+    let RunOutcome::Dispatched(report) = outcome else {
+        panic!("expected a dispatch, got {outcome:?}");
+    };
+
+    let mut names = report
+        .records()
+        .iter()
+        .map(|record| record.bot_name.as_str())
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    names
+}
+
+#[tokio::test]
+async fn csv_file_bots_are_dispatched_when_ready() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+    let csv =
+        "Name,Status,Pool\nbot01,Idle,Finance\n bot02 , logged out ,Finance\nbot03,Offline,HR\n,Idle,HR\n";
+
+    let outcome = run_from_file(&fake, "bots.csv", csv).await;
+
+    assert_eq!(dispatched(&outcome), ["BOT01", "BOT02"]);
+    assert_eq!(RunExitCode::from(&outcome), RunExitCode::Success);
+    assert_eq!(fake.invocations().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn json_file_bots_are_dispatched_and_bad_records_skipped() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+    let json = r#"[
+        { "name": "bot01", "status": "Idle" },
+        { "NAME": "bot02", "STATUS": "Idle" },
+        { "name": 3, "status": "Idle" },
+        { "name": "bot04", "status": "Private" },
+        "bot05"
+    ]"#;
+
+    let outcome = run_from_file(&fake, "bots.json", json).await;
+
+    assert_eq!(dispatched(&outcome), ["BOT01", "BOT02"]);
+}
+
+#[tokio::test]
+async fn csv_file_without_the_name_column_fails_the_run() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+    let path = fake.dir().join("bots.csv");
+    std::fs::write(&path, "Resource,Status\nbot01,Idle\n").unwrap();
+
+    let result = Runner::new(PROCESS, Arc::new(FileBotSource::new(path, ColumnMap::default())))
+        .with_automatec_path(fake.exe_path())
+        .run()
+        .await;
+
+    assert!(result.is_err(), "expected the missing column to fail the run, got {result:?}");
+    assert!(fake.invocations().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn named_resources_are_checked_against_their_live_status() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+    let live = StaticBotSource::from(vec![
+        Bot::new("bot01", BotStatusReady::Idle),
+        Bot::new("bot02", BotStatusNotReady::Offline),
+        Bot::new("bot03", BotStatusReady::LoggedOut),
+    ]);
+    let named = NamedBotSource::new(Arc::new(live), ["bot02", "Bot03", "bot09"]);
+
+    let outcome = Runner::new(PROCESS, Arc::new(named))
+        .with_automatec_path(fake.exe_path())
+        .run()
+        .await
+        .unwrap();
+
+    assert_eq!(dispatched(&outcome), ["BOT03"]);
+}
+
+#[tokio::test]
+async fn named_resources_count_every_record_the_live_source_read() {
+    let live = StaticBotSource::from(vec![
+        Bot::new("bot01", BotStatusReady::Idle),
+        Bot::new("bot02", BotStatusReady::Idle),
+        Bot::new("bot03", BotStatusReady::LoggedOut),
+    ]);
+    let named = NamedBotSource::new(Arc::new(live), ["bot02", "bot09"]);
+
+    let mut handed_over = Vec::new();
+    let read = named
+        .stream_bots(RunLimit::Unlimited, &mut |base_bot| {
+            handed_over.push(base_bot.name().unwrap().to_string());
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(read, 3);
+    assert_eq!(handed_over, ["BOT02"]);
+}
+
+#[tokio::test]
+async fn skipping_the_status_check_runs_every_named_resource() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+    let resources_file = fake.dir().join("resources.txt");
+    std::fs::write(&resources_file, "# change window\nbot02\n\n  BOT01  \nbot03\n").unwrap();
+
+    let cli = Cli::try_parse_from([
+        "bulk_runner_rs",
+        PROCESS,
+        "--resources",
+        "bot01,bot04",
+        "--resources-file",
+        resources_file.to_str().unwrap(),
+        "--skip-status-check",
+        "--automatec-path",
+        fake.exe_path().to_str().unwrap(),
+    ])
+    .unwrap();
+    assert_eq!(cli.resources().unwrap().unwrap(), ["bot01", "bot04", "bot02", "bot03"]);
+
+    let outcome = Runner::try_from(cli).unwrap().run().await.unwrap();
+
+    assert_eq!(dispatched(&outcome), ["BOT01", "BOT02", "BOT03", "BOT04"]);
+}
+
+#[test]
+fn skipping_the_status_check_needs_named_resources() {
+    assert!(Cli::try_parse_from(["bulk_runner_rs", PROCESS, "--skip-status-check"]).is_err());
+}
@@ -0,0 +1,136 @@
+use bulk_runner_query::{AutomateBuilderBase, AutomateCCommander, Error, InputType, ProcessInput, REDACTED};
+
+fn run_on_bot01() -> AutomateBuilderBase {
+    let mut builder = AutomateBuilderBase::new();
+    builder
+        .with_sso()
+        .with_process("Morning Startup")
+        .with_resource("BOT01");
+    builder
+}
+
+fn rejection(builder: &AutomateBuilderBase) -> String {
+    match builder.try_build() {
+        Err(Error::InvalidAutomateCommand(reason)) => reason,
+        Err(e) => panic!("expected an invalid command, got {e}"),
+        Ok(commander) => panic!("expected an invalid command, got {:?}", Vec::<String>::from(commander)),
+    }
+}
+
+#[test]
+fn run_with_inputs_port_and_connection() {
+    let mut builder = run_on_bot01();
+    builder
+        .with_port(8182)
+        .with_dbconname("Production")
+        .with_input(ProcessInput::new("Batch", InputType::Text, "A & B <north>"))
+        .with_input(ProcessInput::new("Retries", InputType::Number, "3"));
+
+    let args = Vec::<String>::from(builder.try_build().unwrap());
+    assert_eq!(
+        args,
+        [
+            "/sso",
+            "/run",
+            "Morning Startup",
+            "/resource",
+            "BOT01",
+            "/port",
+            "8182",
+            "/dbconname",
+            "Production",
+            "/startp",
+            "<inputs><input name=\"Batch\" type=\"text\" value=\"A &amp; B &lt;north&gt;\" /><input name=\"Retries\" \
+             type=\"number\" value=\"3\" /></inputs>",
+        ]
+    );
+}
+
+#[test]
+fn converting_the_builder_keeps_the_inputs() {
+    let with_input = || {
+        let mut builder = run_on_bot01();
+        builder.with_input(ProcessInput::new("Batch", InputType::Text, "A"));
+        builder
+    };
+    let startp = "<inputs><input name=\"Batch\" type=\"text\" value=\"A\" /></inputs>";
+
+    let commander: AutomateCCommander = with_input().into();
+    assert_eq!(Vec::<String>::from(commander)[5..], ["/startp", startp]);
+
+    let command_line = String::from(with_input());
+    assert!(command_line.ends_with(&format!("/startp {startp}")), "{command_line}");
+}
+
+#[test]
+fn password_inputs_are_redacted() {
+    let mut builder = AutomateBuilderBase::new();
+    builder
+        .with_user("svc_bp")
+        .with_password("hunter2")
+        .with_process("Reconcile")
+        .with_input(ProcessInput::new("Account", InputType::Text, "42"))
+        .with_input(ProcessInput::new("Pin", InputType::Password, "9876"));
+
+    let redacted = builder.try_build().unwrap().redacted();
+    assert!(!redacted
+        .iter()
+        .any(|arg| arg.contains("hunter2") || arg.contains("9876")));
+    assert_eq!(redacted[3], REDACTED);
+    assert!(redacted[7].contains(&format!("name=\"Pin\" type=\"password\" value=\"{REDACTED}\"")));
+    assert!(redacted[7].contains("value=\"42\""));
+}
+
+#[test]
+fn queries_and_controls_need_no_process() {
+    let mut builder = AutomateBuilderBase::new();
+    builder
+        .with_sso()
+        .with_status("3f2b8c1e-9a4d-4e7b-8c21-5d6f7a8b9c0d");
+    assert_eq!(
+        Vec::<String>::from(builder.try_build().unwrap()),
+        ["/sso", "/status", "3f2b8c1e-9a4d-4e7b-8c21-5d6f7a8b9c0d"]
+    );
+
+    let mut builder = AutomateBuilderBase::new();
+    builder.with_sso().with_list_queues();
+    assert!(builder.try_build().is_ok());
+}
+
+#[test]
+fn impossible_combinations_are_rejected() {
+    let mut builder = run_on_bot01();
+    builder.with_user("svc_bp").with_password("hunter2");
+    assert_eq!(rejection(&builder), "/sso cannot be combined with /user or /password");
+
+    let mut builder = AutomateBuilderBase::new();
+    builder.with_user("svc_bp").with_process("Morning Startup");
+    assert_eq!(rejection(&builder), "/user needs a /password");
+
+    let mut builder = AutomateBuilderBase::new();
+    builder.with_process("Morning Startup");
+    assert_eq!(rejection(&builder), "no credentials given, use /sso or /user and /password");
+
+    let mut builder = run_on_bot01();
+    builder.with_stop("3f2b8c1e-9a4d-4e7b-8c21-5d6f7a8b9c0d");
+    assert_eq!(rejection(&builder), "only one action can be given, found /run and /stop");
+
+    let mut builder = AutomateBuilderBase::new();
+    builder.with_sso().with_getlog("session").with_resource("BOT01");
+    assert_eq!(rejection(&builder), "/resource can only be used with /run");
+
+    let mut builder = AutomateBuilderBase::new();
+    builder
+        .with_sso()
+        .with_list_queues()
+        .with_input(ProcessInput::new("Batch", InputType::Text, "A"));
+    assert_eq!(rejection(&builder), "/startp can only be used with /run");
+
+    let mut builder = run_on_bot01();
+    builder.with_resource("BOT02");
+    assert_eq!(rejection(&builder), "/resource is given more than once");
+
+    let mut builder = AutomateBuilderBase::new();
+    builder.with_sso();
+    assert!(rejection(&builder).starts_with("no action given"));
+}
@@ -0,0 +1,50 @@
+use bulk_runner_rs::cli::Cli;
+use bulk_runner_rs::config::Profile;
+use clap::Parser;
+
+const PROCESS: &str = "Morning Startup";
+
+/// The example from the `ConfigFile` docs.
+const CONFIG: &str = r#"
+default_profile = "uat"
+
+[profiles.uat]
+db_host = "UATLGDB1"
+db_name = "BP_UAT"
+concurrency_limit = 10
+"#;
+
+fn cli_with_config(extra_args: &[&str]) -> (tempfile::TempDir, Cli) {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("bulk_runner.toml");
+    std::fs::write(&config, CONFIG).unwrap();
+
+    let mut args = vec!["bulk_runner_rs", PROCESS, "--config", config.to_str().unwrap()];
+    args.extend_from_slice(extra_args);
+    let cli = Cli::try_parse_from(args).unwrap().load_config().unwrap();
+    (dir, cli)
+}
+
+#[test]
+fn the_documented_default_profile_is_selected() {
+    let (_dir, cli) = cli_with_config(&[]);
+
+    assert_eq!(cli.profile_name(), Some("uat"));
+    assert_eq!(cli.db_host(), "UATLGDB1");
+    assert_eq!(cli.concurrency_limit(), 10);
+}
+
+#[test]
+fn the_printed_config_parses_back_as_a_profile() {
+    let (_dir, cli) = cli_with_config(&["--exclude", "TEST*", "--limit_total_runnable", "unlimited"]);
+
+    let printed = cli.resolved_config().to_toml().unwrap();
+    assert!(printed.contains("# profile: uat"), "{printed}");
+
+    let profile: Profile = toml::from_str(&printed).unwrap();
+    assert_eq!(profile.db_host.as_deref(), Some("UATLGDB1"));
+    assert_eq!(profile.db_name.as_deref(), Some("BP_UAT"));
+    assert_eq!(profile.concurrency_limit, Some(10));
+    assert_eq!(profile.exclude.unwrap().len(), 1);
+    assert_eq!(profile.limit_total_runnable.unwrap().to_string(), "unlimited");
+}
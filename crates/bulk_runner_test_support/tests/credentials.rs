@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use bulk_runner_bots::{Bot, BotStatusReady};
+use bulk_runner_query::{AutomateAuth, StaticBotSource, REDACTED};
+use bulk_runner_rs::cli::Cli;
+use bulk_runner_rs::credentials::PasswordSource;
+use bulk_runner_rs::{Error, RunOutcome, Runner};
+use bulk_runner_test_support::{FakeAutomateC, FakeScript};
+use clap::Parser;
+
+const FAKE_AUTOMATEC: &str = env!("CARGO_BIN_EXE_fake_automatec");
+const PROCESS: &str = "Morning Startup";
+const PASSWORD: &str = "s3cret pass";
+
+fn runner(fake: &FakeAutomateC) -> Runner {
+    let bots = vec![
+        Bot::new("BOT01", BotStatusReady::Idle),
+        Bot::new("BOT02", BotStatusReady::Idle),
+    ];
+    Runner::new(PROCESS, Arc::new(StaticBotSource::from(bots)))
+        .with_automatec_path(fake.exe_path())
+        .with_auth(AutomateAuth::credentials("svc_bp", PASSWORD))
+}
+
+#[tokio::test]
+async fn user_auth_signs_in_with_user_and_password_and_keeps_the_password_out_of_reports() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+    let report_file = fake.dir().join("report.txt");
+
+    let outcome = runner(&fake)
+        .with_report_file(Some(report_file.clone()))
+        .run()
+        .await
+        .unwrap();
+    let RunOutcome::Dispatched(report) = &outcome else {
+        panic!("expected a dispatch, got {outcome:?}");
+    };
+    assert_eq!(report.succeeded(), 2);
+
+    for invocation in fake.invocations().unwrap() {
+        assert!(!invocation.sso);
+        assert_eq!(invocation.user.as_deref(), Some("svc_bp"));
+    }
+
+    for record in report.records() {
+        assert!(record.argv.iter().any(|arg| arg == REDACTED));
+        assert!(!record.argv.iter().any(|arg| arg.contains(PASSWORD)));
+    }
+    assert!(!std::fs::read_to_string(report_file).unwrap().contains(PASSWORD));
+}
+
+#[tokio::test]
+async fn dry_run_plans_redact_the_password() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+
+    let plan = runner(&fake).plan().await.unwrap();
+
+    assert_eq!(plan.entries().len(), 2);
+    for entry in plan.entries() {
+        assert!(entry.argv.iter().any(|arg| arg == "/user"));
+        assert!(!entry.argv.iter().any(|arg| arg.contains(PASSWORD)));
+    }
+    assert!(fake.invocations().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn plans_never_read_the_password_only_dispatching_does() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+    let bots_file = fake.dir().join("bots.csv");
+    std::fs::write(&bots_file, "name,status\nBOT01,Idle\n").unwrap();
+    let missing_password_file = fake.dir().join("missing.pw");
+
+    let cli = |subcommand: &str| {
+        Cli::try_parse_from([
+            "bulk_runner_rs",
+            subcommand,
+            PROCESS,
+            "--bots-file",
+            bots_file.to_str().unwrap(),
+            "--automatec-path",
+            fake.exe_path().to_str().unwrap(),
+            "--automate-auth",
+            "USER",
+            "--automate-user",
+            "svc_bp",
+            "--automate-password-file",
+            missing_password_file.to_str().unwrap(),
+        ])
+        .unwrap()
+    };
+
+    let outcome = Runner::try_from(cli("plan")).unwrap().run().await.unwrap();
+    let RunOutcome::Planned(plan) = &outcome else {
+        panic!("expected a plan, got {outcome:?}");
+    };
+    assert!(plan.entries()[0].argv.iter().any(|arg| arg == REDACTED));
+
+    let dispatched = Runner::try_from(cli("run")).unwrap().run().await;
+    assert!(matches!(dispatched, Err(Error::AutomatePasswordFile(_, _))), "{dispatched:?}");
+    assert!(fake.invocations().unwrap().is_empty());
+}
+
+#[test]
+fn the_password_cannot_be_given_on_the_command_line() {
+    assert!(Cli::try_parse_from(["bulk_runner_rs", PROCESS, "--automate-password", PASSWORD]).is_err());
+    assert!(Cli::try_parse_from([
+        "bulk_runner_rs",
+        PROCESS,
+        "--automate-auth",
+        "USER",
+        "--automate-password-prompt",
+        "--automate-password-file",
+        "pw.txt"
+    ])
+    .is_err());
+}
+
+#[test]
+fn user_auth_needs_a_user() {
+    let cli = Cli::try_parse_from(["bulk_runner_rs", PROCESS, "--automate-auth", "USER"]).unwrap();
+    if cli.automate_user().is_none() {
+        assert!(matches!(cli.automate_auth(), Err(Error::AutomateUserNotSet)));
+    }
+}
+
+#[cfg(not(windows))]
+#[cfg(unix)]
+#[test]
+fn password_files_must_be_owner_only() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("automatec.pw");
+    std::fs::write(&path, format!("{PASSWORD}\nignored second line\n")).unwrap();
+
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+    assert!(matches!(
+        PasswordSource::File(path.clone()).read("svc_bp"),
+        Err(Error::AutomatePasswordFileInsecure(_, 0o644))
+    ));
+
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+    assert_eq!(PasswordSource::File(path.clone()).read("svc_bp").unwrap(), PASSWORD);
+
+    let cli = Cli::try_parse_from([
+        "bulk_runner_rs",
+        PROCESS,
+        "--automate-auth",
+        "USER",
+        "--automate-user",
+        "svc_bp",
+        "--automate-password-file",
+        path.to_str().unwrap(),
+    ])
+    .unwrap();
+    let auth = cli.automate_auth().unwrap();
+    assert!(
+        matches!(&auth, AutomateAuth::Credentials { user, password } if user == "svc_bp" && password == PASSWORD)
+    );
+    assert!(!format!("{auth:?}").contains(PASSWORD));
+
+    std::fs::write(&path, "\n").unwrap();
+    assert!(matches!(PasswordSource::File(path).read("svc_bp"), Err(Error::AutomatePasswordFile(_, _))));
+}
@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+
+use bulk_runner_rs::{Error, RunExitCode};
+
+fn query_error(error: bulk_runner_query::Error) -> Error {
+    Error::BulkRunnerQuery(error)
+}
+
+fn missing_column() -> bulk_runner_bots::Error {
+    bulk_runner_bots::Error::MissingColumn {
+        column:    "status".to_string(),
+        available: vec!["name".to_string()],
+    }
+}
+
+#[test]
+fn a_database_connection_failure_is_a_query_failure() {
+    let error = query_error(bulk_runner_query::Error::Pool("login failed".to_string()));
+    assert_eq!(RunExitCode::from(&error), RunExitCode::QueryFailure);
+}
+
+#[test]
+fn a_query_missing_a_mapped_column_is_a_query_failure() {
+    let error = query_error(bulk_runner_query::Error::BulkRunnerBots(missing_column()));
+    assert_eq!(RunExitCode::from(&error), RunExitCode::QueryFailure);
+}
+
+#[test]
+fn an_invalid_sql_file_is_a_query_failure() {
+    let error = Error::SqlColumnsMissing(PathBuf::from("bots.sql"), "status".to_string());
+    assert_eq!(RunExitCode::from(&error), RunExitCode::QueryFailure);
+}
+
+#[test]
+fn an_invalid_automatec_command_is_an_error() {
+    let error = query_error(bulk_runner_query::Error::InvalidAutomateCommand("empty".to_string()));
+    assert_eq!(RunExitCode::from(&error), RunExitCode::Error);
+}
+
+#[test]
+fn a_passed_run_deadline_is_an_error() {
+    let error = query_error(bulk_runner_query::Error::RunDeadlinePassed);
+    assert_eq!(RunExitCode::from(&error), RunExitCode::Error);
+}
+
+#[test]
+fn an_unreadable_bots_file_is_an_error() {
+    let error = query_error(bulk_runner_query::Error::InvalidBotsFile(
+        PathBuf::from("bots.csv"),
+        "not valid CSV".to_string(),
+    ));
+    assert_eq!(RunExitCode::from(&error), RunExitCode::Error);
+}
+
+#[test]
+fn a_bot_failing_to_spawn_is_an_error() {
+    let error = query_error(bulk_runner_query::Error::BulkRunnerBots(bulk_runner_bots::Error::Spawn {
+        exe_path: PathBuf::from("AutomateC.exe"),
+        source:   std::io::Error::from(std::io::ErrorKind::NotFound),
+    }));
+    assert_eq!(RunExitCode::from(&error), RunExitCode::Error);
+}
+
+#[test]
+fn other_errors_are_an_error() {
+    let error = Error::NoProcess;
+    assert_eq!(RunExitCode::from(&error), RunExitCode::Error);
+}
@@ -0,0 +1,79 @@
+use std::process::Command;
+
+use bulk_runner_test_support::{FakeAutomateC, FakeResponse, FakeScript};
+
+const FAKE_AUTOMATEC: &str = env!("CARGO_BIN_EXE_fake_automatec");
+
+fn run(fake: &FakeAutomateC, args: &[&str]) -> std::process::Output {
+    Command::new(fake.exe_path()).args(args).output().unwrap()
+}
+
+#[test]
+fn user_and_password_are_accepted_in_place_of_sso() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+
+    let output = run(
+        &fake,
+        &[
+            "/run",
+            "Proc",
+            "/resource",
+            "BOT01",
+            "/user",
+            "svc_bp",
+            "/password",
+            "hunter2",
+        ],
+    );
+
+    assert!(output.status.success());
+    let invocation = &fake.invocations().unwrap()[0];
+    assert_eq!(invocation.user.as_deref(), Some("svc_bp"));
+    assert!(!invocation.sso);
+}
+
+#[test]
+fn switches_are_case_insensitive() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+
+    let output = run(&fake, &["/SSO", "/Run", "Proc", "/RESOURCE", "BOT01"]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "Started process 'Proc' on resource 'BOT01'");
+}
+
+#[test]
+fn bad_argv_exits_with_1() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+
+    for args in [
+        &["/sso", "/run", "Proc", "/resource", "BOT01", "/bogus"][..],
+        &["/sso", "/run", "Proc"],
+        &["/run", "Proc", "/resource", "BOT01"],
+        &["/sso", "/user", "svc_bp", "/run", "Proc", "/resource", "BOT01"],
+        &["/sso", "/run"],
+    ] {
+        let output = run(&fake, args);
+        assert_eq!(output.status.code(), Some(1), "{args:?} should have been rejected");
+        assert!(!output.stderr.is_empty());
+    }
+}
+
+#[test]
+fn scripted_responses_are_matched_by_resource() {
+    let script = FakeScript::default().with_resource(
+        "bot02",
+        FakeResponse::failed(7)
+            .with_stdout("partial")
+            .with_stderr("no session"),
+    );
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &script).unwrap();
+
+    let ok = run(&fake, &["/sso", "/run", "Proc", "/resource", "BOT01"]);
+    let failed = run(&fake, &["/sso", "/run", "Proc", "/resource", "BOT02"]);
+
+    assert!(ok.status.success());
+    assert_eq!(failed.status.code(), Some(7));
+    assert_eq!(failed.stdout, b"partial");
+    assert_eq!(failed.stderr, b"no session");
+}
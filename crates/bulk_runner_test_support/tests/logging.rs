@@ -0,0 +1,99 @@
+use std::path::Path;
+
+use bulk_runner_rs::cli::{Cli, LogFormat, VerbosityLevel};
+use bulk_runner_rs::logging::Logging;
+use clap::Parser;
+use serde_json::Value;
+
+const PROCESS: &str = "Morning Startup";
+
+/// Logs a line at each of info and debug through `logging`, then reads back the day's log file in `dir`.
+fn log_and_read(logging: &Logging, dir: &Path) -> String {
+    let (subscriber, guard) = logging.build().unwrap();
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info!("->> {:<12} - {}", "RUN:: Starting run", "BOT01");
+        tracing::debug!("->> {:<12}", "RUN:: Not logged at INFO");
+    });
+    // Dropping the guard flushes the lines still buffered for the file
+    drop(guard);
+
+    let files = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    assert_eq!(files.len(), 1, "{files:?}");
+    std::fs::read_to_string(&files[0]).unwrap()
+}
+
+#[test]
+fn log_flags_default_to_the_full_format_without_a_file() {
+    let cli = Cli::try_parse_from(["bulk_runner_rs", PROCESS]).unwrap();
+    assert_eq!(cli.log_format(), LogFormat::Full);
+    assert!(cli.log_file().is_none());
+
+    let cli = Cli::try_parse_from([
+        "bulk_runner_rs",
+        PROCESS,
+        "--log-format",
+        "json",
+        "--log-file",
+        "logs/bulk_runner.log",
+    ])
+    .unwrap();
+    assert_eq!(cli.log_format(), LogFormat::Json);
+    assert_eq!(cli.log_file().unwrap(), Path::new("logs/bulk_runner.log"));
+}
+
+#[test]
+fn json_logs_are_written_to_the_dated_log_file_one_object_per_line() {
+    let dir = tempfile::tempdir().unwrap();
+    let logging = Logging::new(VerbosityLevel::Info)
+        .with_format(LogFormat::Json)
+        .with_file(Some(dir.path().join("bulk_runner.log")));
+
+    let logged = log_and_read(&logging, dir.path());
+
+    let file_name = std::fs::read_dir(dir.path())
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .file_name();
+    let file_name = file_name.to_string_lossy();
+    assert!(file_name.starts_with("bulk_runner.") && file_name.ends_with(".log"), "{file_name}");
+
+    let lines = logged.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 1, "{logged}");
+    let line: Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(line["level"], "INFO");
+    assert!(line["fields"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("RUN:: Starting run"));
+}
+
+#[test]
+fn the_log_file_is_never_coloured() {
+    for format in [LogFormat::Full, LogFormat::Pretty, LogFormat::Compact] {
+        let dir = tempfile::tempdir().unwrap();
+        let logging = Logging::new(VerbosityLevel::Debug)
+            .with_format(format)
+            .with_file(Some(dir.path().join("bulk_runner.log")))
+            .with_ansi(true);
+
+        let logged = log_and_read(&logging, dir.path());
+        assert!(logged.contains("RUN:: Starting run"), "{format:?}: {logged}");
+        assert!(logged.contains("RUN:: Not logged at INFO"), "{format:?}: {logged}");
+        assert!(!logged.contains('\u{1b}'), "{format:?} wrote ANSI escapes: {logged}");
+    }
+}
+
+#[test]
+fn an_unwritable_log_file_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let blocker = dir.path().join("not_a_dir");
+    std::fs::write(&blocker, "").unwrap();
+
+    let logging = Logging::new(VerbosityLevel::Info).with_file(Some(blocker.join("bulk_runner.log")));
+    assert!(logging.build().is_err());
+}
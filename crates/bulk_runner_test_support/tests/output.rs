@@ -0,0 +1,166 @@
+use std::sync::Arc;
+
+use bulk_runner_bots::{Bot, BotStatusNotReady, BotStatusReady};
+use bulk_runner_query::StaticBotSource;
+use bulk_runner_rs::cli::{Cli, OutputFormat};
+use bulk_runner_rs::commands::{BotListing, StatusSummary};
+use bulk_runner_rs::filter::{BotFilter, NamePattern};
+use bulk_runner_rs::output::render;
+use bulk_runner_rs::{RunOutcome, Runner};
+use bulk_runner_test_support::{FakeAutomateC, FakeResponse, FakeScript};
+use clap::Parser;
+use serde_json::Value;
+
+const FAKE_AUTOMATEC: &str = env!("CARGO_BIN_EXE_fake_automatec");
+const PROCESS: &str = "Morning Startup";
+
+fn bots() -> Vec<Bot> {
+    vec![
+        Bot::new("OUT01", BotStatusReady::Idle),
+        Bot::new("OUT02", BotStatusReady::LoggedOut),
+        Bot::new("OUT03", BotStatusReady::Idle),
+    ]
+}
+
+fn runner(bots: Vec<Bot>) -> Runner {
+    Runner::new(PROCESS, Arc::new(StaticBotSource::from(bots)))
+}
+
+#[test]
+fn output_defaults_to_a_table_and_plan_format_is_still_accepted() {
+    let cli = Cli::try_parse_from(["bulk_runner_rs", "plan", PROCESS]).unwrap();
+    assert_eq!(cli.output_format(), OutputFormat::Table);
+    assert!(cli.output_file().is_none());
+
+    for args in [
+        ["bulk_runner_rs", PROCESS, "--output", "json"],
+        ["bulk_runner_rs", PROCESS, "--plan-format", "JSON"],
+        ["bulk_runner_rs", PROCESS, "-o", "Json"],
+    ] {
+        assert_eq!(Cli::try_parse_from(args).unwrap().output_format(), OutputFormat::Json);
+    }
+}
+
+#[tokio::test]
+async fn plans_render_as_json_and_csv_with_the_filtered_bots() {
+    let plan = runner(bots())
+        .with_filter(BotFilter::new(Vec::new(), vec!["OUT03".parse::<NamePattern>().unwrap()]))
+        .plan()
+        .await
+        .unwrap();
+
+    let json: Value = serde_json::from_str(&render(&plan, OutputFormat::Json).unwrap()).unwrap();
+    assert_eq!(json["entries"].as_array().unwrap().len(), 2);
+    assert_eq!(json["entries"][1]["bot"], "OUT02");
+    assert_eq!(json["entries"][1]["status"], "IDLE");
+    assert_eq!(json["filtered"][0]["bot_name"], "OUT03");
+
+    let csv = render(&plan, OutputFormat::Csv).unwrap();
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "bot,status,process,argv,filtered_by");
+    assert!(lines[1].starts_with("OUT01,IDLE,Morning Startup,"), "{csv}");
+    assert!(lines[3].starts_with("OUT03,IDLE,,,"), "{csv}");
+    assert_eq!(lines.len(), 4);
+}
+
+#[tokio::test]
+async fn run_results_render_every_outcome_as_json_and_csv() {
+    let script =
+        FakeScript::default().with_resource("OUT02", FakeResponse::failed(3).with_stderr("Resource is busy"));
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &script).unwrap();
+
+    let outcome = runner(bots())
+        .with_automatec_path(fake.exe_path())
+        .run()
+        .await
+        .unwrap();
+    let RunOutcome::Dispatched(report) = &outcome else {
+        panic!("expected a dispatch, got {outcome:?}");
+    };
+
+    let json: Value = serde_json::from_str(&render(report, OutputFormat::Json).unwrap()).unwrap();
+    assert_eq!(json["totals"]["dispatched"], 3);
+    assert_eq!(json["totals"]["started"], 2);
+    assert_eq!(json["totals"]["failed"], 1);
+
+    let records = json["records"].as_array().unwrap();
+    let failed = records.iter().find(|record| record["bot"] == "OUT02").unwrap();
+    assert_eq!(failed["outcome"], "FAILED");
+    assert_eq!(failed["exit_code"], 3);
+    assert_eq!(failed["attempts"], 1);
+    assert!(failed["stderr"].as_str().unwrap().contains("Resource is busy"));
+    let started = records.iter().find(|record| record["bot"] == "OUT01").unwrap();
+    assert_eq!(started["outcome"], "STARTED");
+    assert_eq!(started["exit_code"], 0);
+
+    let csv = render(report, OutputFormat::Csv).unwrap();
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines[0],
+        "bot,process,outcome,exit_code,attempts,started_at,finished_at,duration_ms,session_id,failure_reason,filtered_by"
+    );
+    assert_eq!(lines.len(), 4);
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.starts_with("OUT02,Morning Startup,FAILED,3,1,")),
+        "{csv}"
+    );
+}
+
+#[test]
+fn bot_listings_and_status_render_as_json_and_csv() {
+    let mut bots = bots();
+    bots.push(Bot::new("OUT04", BotStatusNotReady::Offline));
+    bots[0].extras.insert("site".to_string(), "LON".to_string());
+
+    let json: Value = serde_json::from_str(&render(&BotListing(&bots), OutputFormat::Json).unwrap()).unwrap();
+    assert_eq!(json[0]["name"], "OUT01");
+    assert_eq!(json[0]["status"], "IDLE");
+    assert_eq!(json[0]["extras"]["site"], "LON");
+    assert_eq!(json[1]["status"], "LOGGED OUT");
+    assert_eq!(json[3]["available"], false);
+
+    let csv = render(&BotListing(&bots), OutputFormat::Csv).unwrap();
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines[..3],
+        [
+            "name,status,available,site",
+            "OUT01,IDLE,yes,LON",
+            "OUT02,LOGGED OUT,yes,"
+        ]
+    );
+
+    let json: Value =
+        serde_json::from_str(&render(&StatusSummary(&bots), OutputFormat::Json).unwrap()).unwrap();
+    let idle = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|status| status["status"] == "IDLE")
+        .unwrap();
+    assert_eq!(idle["count"], 2);
+    assert_eq!(idle["resources"], serde_json::json!(["OUT01", "OUT03"]));
+
+    let csv = render(&StatusSummary(&bots), OutputFormat::Csv).unwrap();
+    assert!(csv.starts_with("status,count,available,resources\n"), "{csv}");
+    assert!(csv.contains("IDLE,2,yes,OUT01 OUT03\n"), "{csv}");
+}
+
+#[tokio::test]
+async fn output_file_receives_the_results_instead_of_stdout() {
+    let dir = tempfile::tempdir().unwrap();
+    let output_file = dir.path().join("plan.json");
+
+    runner(bots())
+        .with_dry_run(true)
+        .with_output_format(OutputFormat::Json)
+        .with_output_file(Some(output_file.clone()))
+        .run()
+        .await
+        .unwrap();
+
+    let json: Value = serde_json::from_str(&std::fs::read_to_string(&output_file).unwrap()).unwrap();
+    assert_eq!(json["entries"].as_array().unwrap().len(), 3);
+}
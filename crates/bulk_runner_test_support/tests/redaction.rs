@@ -0,0 +1,120 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use bulk_runner_bots::{Bot, BotStatusReady, RedactedArgs, REDACTED};
+use bulk_runner_query::{
+    build_commander,
+    AutomateAuth,
+    AutomateBuilderBase,
+    InputType,
+    ProcessInput,
+    StaticBotSource,
+};
+use bulk_runner_rs::{RunOutcome, Runner};
+use bulk_runner_test_support::{FakeAutomateC, FakeScript};
+use tracing_subscriber::filter::LevelFilter;
+
+const FAKE_AUTOMATEC: &str = env!("CARGO_BIN_EXE_fake_automatec");
+const PROCESS: &str = "Morning Startup";
+const PASSWORD: &str = "s3cret pass";
+
+/// Everything logged by any test in this file, at every level.
+///
+/// The subscriber is global so events from spawned tasks and blocking threads are captured too.
+fn captured_logs() -> Arc<Mutex<Vec<u8>>> {
+    static LOGS: OnceLock<Arc<Mutex<Vec<u8>>>> = OnceLock::new();
+
+    LOGS.get_or_init(|| {
+        let logs = Arc::new(Mutex::new(Vec::new()));
+        let writer = Arc::clone(&logs);
+        tracing_subscriber::fmt()
+            .with_max_level(LevelFilter::TRACE)
+            .with_ansi(false)
+            .with_writer(move || CapturedWriter(Arc::clone(&writer)))
+            .init();
+        logs
+    })
+    .clone()
+}
+
+struct CapturedWriter(Arc<Mutex<Vec<u8>>>);
+
+impl Write for CapturedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The captured lines mentioning `bot`, after checking nothing captured holds the password.
+fn logged_lines_for(logs: &Mutex<Vec<u8>>, bot: &str) -> Vec<String> {
+    let logs = String::from_utf8(logs.lock().unwrap().clone()).unwrap();
+    assert!(!logs.contains(PASSWORD), "the password was logged:\n{logs}");
+
+    logs.lines()
+        .filter(|line| line.contains(bot))
+        .map(str::to_string)
+        .collect()
+}
+
+fn runner(bot: &str) -> Runner {
+    Runner::new(PROCESS, Arc::new(StaticBotSource::from(vec![Bot::new(bot, BotStatusReady::Idle)])))
+        .with_auth(AutomateAuth::credentials("svc_bp", PASSWORD))
+}
+
+#[tokio::test]
+async fn automatec_gets_the_password_but_debug_logs_of_its_argv_are_redacted() {
+    let logs = captured_logs();
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+
+    let outcome = runner("REDACT01")
+        .with_automatec_path(fake.exe_path())
+        .run()
+        .await
+        .unwrap();
+    let RunOutcome::Dispatched(report) = &outcome else {
+        panic!("expected a dispatch, got {outcome:?}");
+    };
+    assert_eq!(report.succeeded(), 1);
+
+    let invocations = fake.invocations().unwrap();
+    assert!(invocations[0].args.iter().any(|arg| arg.contains(PASSWORD)));
+
+    let lines = logged_lines_for(&logs, "REDACT01");
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.contains("DISPATCH:: Commander") && line.contains(REDACTED)),
+        "no redacted command line was logged:\n{}",
+        lines.join("\n")
+    );
+}
+
+#[test]
+fn commanders_display_and_debug_with_their_secrets_redacted() {
+    let bot = Bot::new("REDACT03", BotStatusReady::Idle);
+    let commander = build_commander(&bot, PROCESS, &AutomateAuth::credentials("svc_bp", PASSWORD)).unwrap();
+
+    let displayed = commander.to_string();
+    assert_eq!(displayed, format!("/user svc_bp /password {REDACTED} /run {PROCESS} /resource REDACT03"));
+    assert!(!format!("{commander:?}").contains(PASSWORD));
+    assert!(!format!("{:?}", AutomateAuth::credentials("svc_bp", PASSWORD)).contains(PASSWORD));
+
+    let mut builder = AutomateBuilderBase::new();
+    builder
+        .with_sso()
+        .with_process(PROCESS)
+        .with_resource("REDACT03")
+        .with_input(ProcessInput::new("Vault key", InputType::Password, PASSWORD))
+        .with_input(ProcessInput::new("Batch", InputType::Text, "north"));
+    let args = Vec::<String>::from(builder.build());
+
+    let redacted = format!("{} {:?}", RedactedArgs::new(&args), RedactedArgs::new(&args));
+    assert!(!redacted.contains(PASSWORD));
+    assert!(redacted.contains(REDACTED));
+    assert!(redacted.contains("north"));
+}
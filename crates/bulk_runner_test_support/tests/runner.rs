@@ -0,0 +1,719 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use bulk_runner_bots::{
+    parse_session_id,
+    Bot,
+    BotStatusNotReady,
+    BotStatusReady,
+    FailureReason,
+    ScriptedExecutor,
+};
+use bulk_runner_query::{RetryPolicy, RunLimit, StaticBotSource};
+use bulk_runner_rs::cli::Cli;
+use bulk_runner_rs::filter::{BotFilter, NamePattern};
+use bulk_runner_rs::process_map::ProcessMap;
+use bulk_runner_rs::{RunExitCode, RunOutcome, Runner};
+use bulk_runner_test_support::{max_concurrency, FakeAutomateC, FakeResponse, FakeScript};
+use clap::Parser;
+
+const FAKE_AUTOMATEC: &str = env!("CARGO_BIN_EXE_fake_automatec");
+const PROCESS: &str = "Morning Startup";
+
+fn idle_bots(count: usize) -> Vec<Bot> {
+    (1..=count)
+        .map(|n| Bot::new(format!("bot{n:02}"), BotStatusReady::Idle))
+        .collect()
+}
+
+fn runner(fake: &FakeAutomateC, bots: Vec<Bot>) -> Runner {
+    Runner::new(PROCESS, Arc::new(StaticBotSource::from(bots))).with_automatec_path(fake.exe_path())
+}
+
+#[tokio::test]
+async fn every_bot_is_dispatched_with_the_automatec_argv() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+
+    let outcome = runner(&fake, idle_bots(5)).run().await.unwrap();
+    let RunOutcome::Dispatched(report) = &outcome else {
+        panic!("expected a dispatch, got {outcome:?}");
+    };
+
+    assert_eq!(report.len(), 5);
+    assert_eq!(report.succeeded(), 5);
+    assert_eq!(RunExitCode::from(&outcome), RunExitCode::Success);
+
+    let mut invocations = fake.invocations().unwrap();
+    invocations.sort_by(|a, b| a.resource.cmp(&b.resource));
+    assert_eq!(invocations.len(), 5);
+    assert_eq!(invocations[0].args, ["/sso", "/run", PROCESS, "/resource", "BOT01"]);
+    assert!(invocations.iter().all(|run| run.sso && run.exit_code == 0));
+}
+
+#[tokio::test]
+async fn concurrency_limit_caps_simultaneous_dispatches() {
+    let script = FakeScript::new(FakeResponse::started().with_delay(Duration::from_millis(250)));
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &script).unwrap();
+
+    let outcome = runner(&fake, idle_bots(8))
+        .with_concurrency_limit(3)
+        .run()
+        .await
+        .unwrap();
+    assert_eq!(RunExitCode::from(&outcome), RunExitCode::Success);
+
+    let invocations = fake.invocations().unwrap();
+    assert_eq!(invocations.len(), 8);
+    let most = max_concurrency(&invocations);
+    assert!(most <= 3, "{most} dispatches ran at once, the limit was 3");
+    assert!(most > 1, "dispatches never overlapped, expected them to run concurrently");
+}
+
+#[tokio::test]
+async fn failed_dispatches_are_reported_with_their_output() {
+    let script = FakeScript::default()
+        .with_resource("BOT02", FakeResponse::failed(3).with_stderr("Resource BOT02 is not connected"));
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &script).unwrap();
+    let report_file = fake.dir().join("report.txt");
+
+    let outcome = runner(&fake, idle_bots(3))
+        .with_report_file(Some(report_file.clone()))
+        .run()
+        .await
+        .unwrap();
+    let RunOutcome::Dispatched(report) = &outcome else {
+        panic!("expected a dispatch, got {outcome:?}");
+    };
+
+    assert_eq!(report.succeeded(), 2);
+    assert_eq!(report.failed(), 1);
+    assert_eq!(RunExitCode::from(&outcome), RunExitCode::PartialFailure);
+
+    let failed = report
+        .records()
+        .iter()
+        .find(|record| record.bot_name == "BOT02")
+        .unwrap();
+    assert_eq!(failed.exit_code(), Some(3));
+    assert_eq!(failed.output().unwrap().stderr(), "Resource BOT02 is not connected");
+
+    let written = std::fs::read_to_string(report_file).unwrap();
+    assert!(written.contains("== BOT02 (FAILED) =="));
+    assert!(written.contains("Resource BOT02 is not connected"));
+}
+
+#[tokio::test]
+async fn every_dispatch_failing_is_a_total_failure() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::new(FakeResponse::failed(1))).unwrap();
+
+    let outcome = runner(&fake, idle_bots(2)).run().await.unwrap();
+
+    assert_eq!(RunExitCode::from(&outcome), RunExitCode::TotalFailure);
+}
+
+#[tokio::test]
+async fn only_ready_bots_are_dispatched_up_to_the_limit() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+    let mut bots = vec![
+        Bot::new("offline01", BotStatusNotReady::Offline),
+        Bot::new("loggedout01", BotStatusReady::LoggedOut),
+    ];
+    bots.extend(idle_bots(4));
+
+    let outcome = runner(&fake, bots)
+        .with_limit_total_runnable(RunLimit::Limited(3))
+        .run()
+        .await
+        .unwrap();
+    let RunOutcome::Dispatched(report) = &outcome else {
+        panic!("expected a dispatch, got {outcome:?}");
+    };
+
+    let mut dispatched = report
+        .records()
+        .iter()
+        .map(|record| record.bot_name.as_str())
+        .collect::<Vec<_>>();
+    dispatched.sort_unstable();
+    assert_eq!(dispatched, ["BOT01", "BOT02", "LOGGEDOUT01"]);
+    assert_eq!(fake.invocations().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn no_ready_bots_means_no_eligible_bots() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+
+    let outcome = runner(&fake, vec![Bot::new("private01", BotStatusNotReady::Private)])
+        .run()
+        .await
+        .unwrap();
+
+    assert_eq!(RunExitCode::from(&outcome), RunExitCode::NoEligibleBots);
+    assert!(fake.invocations().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn dry_run_plans_without_spawning() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+
+    let outcome = runner(&fake, idle_bots(2))
+        .with_dry_run(true)
+        .run()
+        .await
+        .unwrap();
+    let RunOutcome::Planned(plan) = &outcome else {
+        panic!("expected a plan, got {outcome:?}");
+    };
+
+    assert_eq!(plan.entries().len(), 2);
+    assert_eq!(plan.entries()[0].argv[0], fake.exe_path().display().to_string());
+    assert!(fake.invocations().unwrap().is_empty());
+}
+
+fn patterns(patterns: &[&str]) -> Vec<NamePattern> {
+    patterns.iter().map(|pattern| pattern.parse().unwrap()).collect()
+}
+
+#[tokio::test]
+async fn filtered_bots_are_reported_with_their_rule_instead_of_dispatched() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+    let mut bots = idle_bots(3);
+    bots.push(Bot::new("vip01", BotStatusReady::Idle));
+    bots.push(Bot::new("other01", BotStatusReady::Idle));
+
+    let filter = BotFilter::new(patterns(&["bot*", "re:VIP\\d+"]), patterns(&["bot?2", "VIP*"]));
+    let outcome = runner(&fake, bots).with_filter(filter).run().await.unwrap();
+    let RunOutcome::Dispatched(report) = &outcome else {
+        panic!("expected a dispatch, got {outcome:?}");
+    };
+
+    let mut dispatched = report
+        .records()
+        .iter()
+        .map(|record| record.bot_name.as_str())
+        .collect::<Vec<_>>();
+    dispatched.sort_unstable();
+    assert_eq!(dispatched, ["BOT01", "BOT03"]);
+
+    let mut filtered = report
+        .filtered()
+        .iter()
+        .map(|bot| (bot.bot_name.as_str(), bot.rule.as_str()))
+        .collect::<Vec<_>>();
+    filtered.sort_unstable();
+    assert_eq!(
+        filtered,
+        [
+            ("BOT02", "--exclude bot?2"),
+            ("OTHER01", "not matched by any --include"),
+            ("VIP01", "--exclude VIP*"),
+        ]
+    );
+    assert_eq!(fake.invocations().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn dry_run_plan_lists_filtered_bots() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+
+    let outcome = runner(&fake, idle_bots(3))
+        .with_filter(BotFilter::new(Vec::new(), patterns(&["*3"])))
+        .with_dry_run(true)
+        .run()
+        .await
+        .unwrap();
+    let RunOutcome::Planned(plan) = &outcome else {
+        panic!("expected a plan, got {outcome:?}");
+    };
+
+    assert_eq!(plan.entries().len(), 2);
+    assert_eq!(plan.filtered()[0].bot_name, "BOT03");
+    assert!(plan.to_string().contains("1 bot(s) filtered out"));
+}
+
+#[test]
+fn invalid_regex_patterns_are_rejected() {
+    assert!("re:BOT[".parse::<NamePattern>().is_err());
+    assert!("BOT[".parse::<NamePattern>().is_ok());
+}
+
+#[tokio::test]
+async fn each_bot_runs_the_process_it_is_mapped_to() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+    let map_file = fake.dir().join("changeover.toml");
+    std::fs::write(
+        &map_file,
+        "\"Invoice Processing\" = [\"bot01\", \"BOT02\"]\n\"Claims Intake\" = [\"bot03\"]\n",
+    )
+    .unwrap();
+
+    let mut bots = idle_bots(5);
+    bots[3]
+        .extras
+        .insert("next_process".to_string(), "Payroll".to_string());
+    bots[2]
+        .extras
+        .insert("next_process".to_string(), "Ignored".to_string());
+
+    let processes = ProcessMap::new(None)
+        .with_resources(ProcessMap::load(&map_file).unwrap())
+        .with_column(Some("next_process".to_string()));
+    let outcome = Runner::new(processes, Arc::new(StaticBotSource::from(bots)))
+        .with_automatec_path(fake.exe_path())
+        .run()
+        .await
+        .unwrap();
+    let RunOutcome::Dispatched(report) = &outcome else {
+        panic!("expected a dispatch, got {outcome:?}");
+    };
+
+    let mut dispatched = report
+        .records()
+        .iter()
+        .map(|record| (record.bot_name.as_str(), record.process.as_str()))
+        .collect::<Vec<_>>();
+    dispatched.sort_unstable();
+    assert_eq!(
+        dispatched,
+        [
+            ("BOT01", "Invoice Processing"),
+            ("BOT02", "Invoice Processing"),
+            ("BOT03", "Claims Intake"),
+            ("BOT04", "Payroll"),
+        ]
+    );
+    assert_eq!(report.filtered()[0].bot_name, "BOT05");
+    assert_eq!(report.filtered()[0].rule, "no process mapped");
+
+    let mut invocations = fake.invocations().unwrap();
+    invocations.sort_by(|a, b| a.resource.cmp(&b.resource));
+    assert_eq!(invocations[3].process.as_deref(), Some("Payroll"));
+}
+
+#[test]
+fn csv_process_maps_are_read_and_conflicts_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let map_file = dir.path().join("changeover.csv");
+
+    std::fs::write(&map_file, "Resource,Process\nbot01, Invoice Processing\nbot02,Claims Intake\n").unwrap();
+    assert_eq!(
+        ProcessMap::load(&map_file).unwrap(),
+        [
+            ("bot01".to_string(), "Invoice Processing".to_string()),
+            ("bot02".to_string(), "Claims Intake".to_string())
+        ]
+    );
+
+    std::fs::write(&map_file, "resource,process\nbot01,Invoice Processing\nBOT01,Claims Intake\n").unwrap();
+    assert!(ProcessMap::load(&map_file).is_err());
+}
+
+#[tokio::test]
+async fn hung_dispatches_are_killed_and_recorded_as_timed_out() {
+    let script = FakeScript::default()
+        .with_resource("BOT02", FakeResponse::started().with_delay(Duration::from_secs(30)));
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &script).unwrap();
+
+    let started = Instant::now();
+    let outcome = runner(&fake, idle_bots(3))
+        .with_dispatch_timeout(Some(Duration::from_millis(500)))
+        .run()
+        .await
+        .unwrap();
+    assert!(started.elapsed() < Duration::from_secs(10), "the hung dispatch held up the run");
+
+    let RunOutcome::Dispatched(report) = &outcome else {
+        panic!("expected a dispatch, got {outcome:?}");
+    };
+    assert_eq!(report.succeeded(), 2);
+    assert_eq!(report.timed_out(), 1);
+    assert_eq!(RunExitCode::from(&outcome), RunExitCode::PartialFailure);
+
+    let hung = report
+        .records()
+        .iter()
+        .find(|record| record.bot_name == "BOT02")
+        .unwrap();
+    assert_eq!(hung.outcome_label(), "TIMED OUT after 500ms");
+
+    // Killed before it could log its invocation
+    let invocations = fake.invocations().unwrap();
+    assert!(invocations
+        .iter()
+        .all(|run| run.resource.as_deref() != Some("BOT02")));
+}
+
+#[tokio::test]
+async fn nothing_is_dispatched_past_the_run_deadline() {
+    let script = FakeScript::new(FakeResponse::started().with_delay(Duration::from_millis(400)));
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &script).unwrap();
+
+    let outcome = runner(&fake, idle_bots(4))
+        .with_concurrency_limit(1)
+        .with_run_deadline(Some(Duration::from_millis(700)))
+        .run()
+        .await
+        .unwrap();
+    let RunOutcome::Dispatched(report) = &outcome else {
+        panic!("expected a dispatch, got {outcome:?}");
+    };
+
+    assert_eq!(report.len(), 4);
+    assert_eq!(report.succeeded(), 1);
+    assert_eq!(report.timed_out(), 1);
+    assert_eq!(report.errored(), 2);
+    assert_eq!(RunExitCode::from(&outcome), RunExitCode::PartialFailure);
+    assert_eq!(fake.invocations().unwrap().len(), 1);
+}
+
+fn quick_retries(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy::new(max_attempts).with_backoff(Duration::from_millis(10), Duration::from_millis(50))
+}
+
+#[tokio::test]
+async fn transient_failures_are_retried_until_the_bot_starts() {
+    let script = FakeScript::default()
+        .with_attempts(
+            "BOT02",
+            vec![
+                FakeResponse::failed(3).with_stderr("App server busy, try again later"),
+                FakeResponse::failed(3).with_stderr("App server busy, try again later"),
+                FakeResponse::started(),
+            ],
+        )
+        .with_resource("BOT03", FakeResponse::failed(5).with_stderr("Process not found"));
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &script).unwrap();
+    let report_file = fake.dir().join("report.txt");
+
+    let retry = quick_retries(3).with_stderr_patterns(vec![regex::Regex::new("busy").unwrap()]);
+    let outcome = runner(&fake, idle_bots(3))
+        .with_retry_policy(retry)
+        .with_report_file(Some(report_file.clone()))
+        .run()
+        .await
+        .unwrap();
+    let RunOutcome::Dispatched(report) = &outcome else {
+        panic!("expected a dispatch, got {outcome:?}");
+    };
+
+    assert_eq!(report.succeeded(), 2);
+    assert_eq!(report.failed(), 1);
+    assert_eq!(report.retried(), 1);
+    assert_eq!(RunExitCode::from(&outcome), RunExitCode::PartialFailure);
+
+    let attempts = |name: &str| {
+        let record = report
+            .records()
+            .iter()
+            .find(|record| record.bot_name == name)
+            .unwrap();
+        (record.attempts(), record.is_success())
+    };
+    assert_eq!(attempts("BOT01"), (1, true));
+    assert_eq!(attempts("BOT02"), (3, true));
+    // Its stderr matches no retry pattern
+    assert_eq!(attempts("BOT03"), (1, false));
+
+    let flaky = report
+        .records()
+        .iter()
+        .find(|record| record.bot_name == "BOT02")
+        .unwrap();
+    assert!(flaky.retried.iter().all(|attempt| attempt.exit_code() == Some(3)));
+
+    let invocations = fake.invocations().unwrap();
+    assert_eq!(invocations.len(), 5);
+
+    let written = std::fs::read_to_string(report_file).unwrap();
+    assert!(written.contains("attempt 2: FAILED"));
+    assert!(written.contains("App server busy"));
+}
+
+#[tokio::test]
+async fn retries_stop_at_max_attempts() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::new(FakeResponse::failed(2))).unwrap();
+
+    let outcome = runner(&fake, idle_bots(2))
+        .with_retry_policy(quick_retries(3).with_exit_codes(vec![2]))
+        .run()
+        .await
+        .unwrap();
+    let RunOutcome::Dispatched(report) = &outcome else {
+        panic!("expected a dispatch, got {outcome:?}");
+    };
+
+    assert!(report.records().iter().all(|record| record.attempts() == 3));
+    assert_eq!(RunExitCode::from(&outcome), RunExitCode::TotalFailure);
+    assert_eq!(fake.invocations().unwrap().len(), 6);
+}
+
+#[test]
+fn retry_flags_are_validated() {
+    assert!(Cli::try_parse_from(["bulk_runner_rs", PROCESS, "--max-attempts", "0"]).is_err());
+
+    let cli = Cli::try_parse_from([
+        "bulk_runner_rs",
+        PROCESS,
+        "--max-attempts",
+        "3",
+        "--retry-stderr",
+        "busy(",
+    ])
+    .unwrap();
+    assert!(cli.retry_policy().is_err());
+}
+
+#[tokio::test]
+async fn a_missing_automatec_is_reported_per_bot_rather_than_panicking() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+    let missing = fake.dir().join("missing_automatec");
+
+    let outcome = runner(&fake, idle_bots(2))
+        .with_automatec_path(&missing)
+        .with_retry_policy(quick_retries(3))
+        .run()
+        .await
+        .unwrap();
+    let RunOutcome::Dispatched(report) = &outcome else {
+        panic!("expected a dispatch, got {outcome:?}");
+    };
+
+    assert_eq!(report.errored(), 2);
+    assert_eq!(RunExitCode::from(&outcome), RunExitCode::TotalFailure);
+    for record in report.records() {
+        // Spawn failures aren't transient, so they are never retried
+        assert_eq!(record.attempts(), 1);
+        assert!(
+            record
+                .outcome_label()
+                .contains(&format!("Could not spawn {}", missing.display())),
+            "unexpected outcome: {}",
+            record.outcome_label()
+        );
+    }
+}
+
+#[tokio::test]
+async fn session_ids_and_failure_reasons_are_read_from_automatec_output() {
+    let session = "3f2b8c1e-9a4d-4e7b-8c21-5d6f7a8b9c0d";
+    let script = FakeScript::default()
+        .with_resource(
+            "BOT01",
+            FakeResponse::started().with_stdout(format!("Started process. Session ID: {{{session}}}\n")),
+        )
+        .with_resource(
+            "BOT02",
+            FakeResponse::failed(1).with_stderr("Process 'Morning Startup' is not published"),
+        )
+        .with_resource("BOT03", FakeResponse::failed(1).with_stderr("The resource BOT03 is busy"))
+        .with_resource("BOT04", FakeResponse::failed(1).with_stderr("Something odd happened\nat line 2"));
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &script).unwrap();
+    let report_file = fake.dir().join("report.txt");
+
+    let outcome = runner(&fake, idle_bots(4))
+        .with_report_file(Some(report_file.clone()))
+        .run()
+        .await
+        .unwrap();
+    let RunOutcome::Dispatched(report) = &outcome else {
+        panic!("expected a dispatch, got {outcome:?}");
+    };
+
+    let record = |name: &str| {
+        report
+            .records()
+            .iter()
+            .find(|record| record.bot_name == name)
+            .unwrap()
+    };
+    assert_eq!(record("BOT01").session_id(), Some(session));
+    assert_eq!(record("BOT01").failure_reason(), None);
+    assert_eq!(record("BOT02").failure_reason(), Some(&FailureReason::NotPublished));
+    assert_eq!(record("BOT03").failure_reason(), Some(&FailureReason::ResourceBusy));
+    assert_eq!(
+        record("BOT04").failure_reason(),
+        Some(&FailureReason::Unrecognised("Something odd happened".to_string()))
+    );
+
+    let written = std::fs::read_to_string(report_file).unwrap();
+    assert!(written.contains(&format!("session:  {session}")));
+    assert!(written.contains("reason:   process not published"));
+}
+
+#[test]
+fn failure_reasons_are_classified_from_either_stream() {
+    let cases = [
+        ("", "User does not have permission to run this process", FailureReason::PermissionDenied),
+        ("ERROR: Process not found - Evening Shutdown", "", FailureReason::ProcessNotFound),
+        ("", "Resource ROBOT07 is not connected", FailureReason::ResourceUnavailable),
+        ("", "ROBOT07 is already running a session", FailureReason::ResourceBusy),
+        ("", "", FailureReason::Unrecognised(String::new())),
+    ];
+
+    for (stdout, stderr, reason) in cases {
+        assert_eq!(FailureReason::classify(stdout, stderr), reason, "{stdout:?} / {stderr:?}");
+    }
+
+    assert_eq!(parse_session_id("no session was started"), None);
+    assert_eq!(
+        parse_session_id(
+            "resource 0f0e0d0c-0000-4000-8000-000000000001, session 11111111-2222-3333-4444-555555555555"
+        )
+        .as_deref(),
+        Some("11111111-2222-3333-4444-555555555555")
+    );
+}
+
+#[tokio::test]
+async fn bots_that_are_not_available_are_reported_and_the_rest_still_dispatched() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+    let bots = vec![
+        Bot::new("bot01", BotStatusReady::Idle),
+        Bot::new("pending01", BotStatusReady::Pending),
+        Bot::new("bot02", BotStatusReady::Idle),
+        Bot::new("offline01", BotStatusNotReady::Offline),
+        Bot::new("bot03", BotStatusReady::Idle),
+    ];
+
+    let outcome = runner(&fake, bots).run().await.unwrap();
+    let RunOutcome::Dispatched(report) = &outcome else {
+        panic!("expected a dispatch, got {outcome:?}");
+    };
+
+    let mut dispatched = report
+        .records()
+        .iter()
+        .map(|record| record.bot_name.as_str())
+        .collect::<Vec<_>>();
+    dispatched.sort_unstable();
+    assert_eq!(dispatched, ["BOT01", "BOT02", "BOT03"]);
+
+    let filtered = report
+        .filtered()
+        .iter()
+        .map(|bot| (bot.bot_name.as_str(), bot.status.as_str(), bot.rule.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        filtered,
+        [
+            ("PENDING01", "PENDING", "not available"),
+            ("OFFLINE01", "OFFLINE", "not available"),
+        ]
+    );
+    assert_eq!(report.len() + report.filtered().len(), 5);
+    assert!(bulk_runner_rs::report::summary_table(report).contains("3 dispatched: 3 started"));
+}
+
+#[tokio::test]
+async fn only_available_bots_count_toward_the_limit() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+    let bots = vec![
+        Bot::new("pending01", BotStatusReady::Pending),
+        Bot::new("pending02", BotStatusReady::Pending),
+        Bot::new("bot01", BotStatusReady::Idle),
+        Bot::new("pending03", BotStatusReady::Pending),
+        Bot::new("offline01", BotStatusNotReady::Offline),
+        Bot::new("bot02", BotStatusReady::Idle),
+        Bot::new("bot03", BotStatusReady::LoggedOut),
+        Bot::new("bot04", BotStatusReady::Idle),
+    ];
+
+    let outcome = runner(&fake, bots)
+        .with_limit_total_runnable(RunLimit::Limited(3))
+        .run()
+        .await
+        .unwrap();
+    let RunOutcome::Dispatched(report) = &outcome else {
+        panic!("expected a dispatch, got {outcome:?}");
+    };
+
+    let mut dispatched = report
+        .records()
+        .iter()
+        .map(|record| record.bot_name.as_str())
+        .collect::<Vec<_>>();
+    dispatched.sort_unstable();
+    assert_eq!(dispatched, ["BOT01", "BOT02", "BOT03"]);
+    assert_eq!(report.filtered().len(), 4);
+    assert_eq!(fake.invocations().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn filtered_bots_do_not_use_up_the_limit() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+    let mut bots = vec![
+        Bot::new("test01", BotStatusReady::Idle),
+        Bot::new("test02", BotStatusReady::Idle),
+    ];
+    bots.extend(idle_bots(4));
+
+    let outcome = runner(&fake, bots)
+        .with_filter(BotFilter::new(Vec::new(), patterns(&["TEST*"])))
+        .with_limit_total_runnable(RunLimit::Limited(3))
+        .run()
+        .await
+        .unwrap();
+    let RunOutcome::Dispatched(report) = &outcome else {
+        panic!("expected a dispatch, got {outcome:?}");
+    };
+
+    let mut dispatched = report
+        .records()
+        .iter()
+        .map(|record| record.bot_name.as_str())
+        .collect::<Vec<_>>();
+    dispatched.sort_unstable();
+    assert_eq!(dispatched, ["BOT01", "BOT02", "BOT03"]);
+    assert_eq!(report.filtered().len(), 2);
+    assert_eq!(fake.invocations().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn limit_and_exclude_from_the_cli_plan_the_first_bots_not_excluded() {
+    let mut bots = vec![Bot::new("test01", BotStatusReady::Idle)];
+    bots.extend(idle_bots(3));
+    bots.insert(2, Bot::new("test02", BotStatusReady::Idle));
+
+    let cli = Cli::try_parse_from(["bulk_runner_rs", PROCESS, "-l", "2", "--exclude", "test*"]).unwrap();
+    let plan = Runner::new(PROCESS, Arc::new(StaticBotSource::from(bots)))
+        .with_limit_total_runnable(cli.limit_total_runnable())
+        .with_filter(cli.bot_filter())
+        .plan()
+        .await
+        .unwrap();
+
+    let planned = plan
+        .entries()
+        .iter()
+        .map(|entry| entry.bot.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(planned, ["BOT01", "BOT02"]);
+    assert_eq!(plan.filtered().len(), 2);
+}
+
+#[tokio::test]
+async fn an_injected_executor_is_kept_whichever_order_the_path_is_set_in() {
+    let fake = FakeAutomateC::install(FAKE_AUTOMATEC, &FakeScript::default()).unwrap();
+    let source = Arc::new(StaticBotSource::from(idle_bots(3)));
+
+    let executor = Arc::new(ScriptedExecutor::new());
+
+    for runner in [
+        Runner::new(PROCESS, source.clone())
+            .with_executor(executor.clone())
+            .with_automatec_path(fake.exe_path()),
+        Runner::new(PROCESS, source.clone())
+            .with_automatec_path(fake.exe_path())
+            .with_executor(executor.clone()),
+    ] {
+        let outcome = runner.run().await.unwrap();
+        let RunOutcome::Dispatched(report) = &outcome else {
+            panic!("expected a dispatch, got {outcome:?}");
+        };
+        assert_eq!(report.succeeded(), 3);
+    }
+
+    assert_eq!(executor.calls().len(), 6);
+
+    assert!(fake.invocations().unwrap().is_empty());
+}
@@ -0,0 +1,139 @@
+use bulk_runner_bots::ColumnMap;
+use bulk_runner_query::SqlValidation;
+use bulk_runner_rs::cli::{Cli, Command};
+use bulk_runner_rs::commands::{bots_table, read_bots, status_table, validation_table};
+use bulk_runner_rs::{Error, RunExitCode, Runner};
+use clap::Parser;
+
+const PROCESS: &str = "Morning Startup";
+
+const BOTS_CSV: &str = "\
+name,status,site
+BOT01,Idle,LON
+BOT02,Pending,LON
+BOT03,Offline,NYC
+BOT04,Logged Out,NYC
+BOT05,Idle,NYC
+";
+
+#[test]
+fn the_bare_process_name_still_runs_it() {
+    let cli = Cli::try_parse_from(["bulk_runner_rs", PROCESS, "-c", "5"]).unwrap();
+
+    assert_eq!(
+        cli.command(),
+        Command::Run {
+            process: Some(PROCESS.to_string()),
+        }
+    );
+    assert_eq!(cli.process(), Some(PROCESS));
+    assert_eq!(cli.concurrency_limit(), 5);
+    assert!(!cli.dry_run());
+}
+
+#[test]
+fn flags_are_accepted_either_side_of_the_subcommand() {
+    for args in [
+        ["bulk_runner_rs", "-c", "5", "run", PROCESS],
+        ["bulk_runner_rs", "run", PROCESS, "-c", "5"],
+    ] {
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert_eq!(cli.process(), Some(PROCESS));
+        assert_eq!(cli.concurrency_limit(), 5);
+        assert!(cli.dispatches());
+    }
+}
+
+#[test]
+fn plan_is_a_dry_run_and_the_listing_subcommands_never_dispatch() {
+    let cli = Cli::try_parse_from(["bulk_runner_rs", "plan", PROCESS]).unwrap();
+    assert_eq!(cli.process(), Some(PROCESS));
+    assert!(cli.dry_run());
+    assert!(!cli.dispatches());
+    assert!(cli.process_map().is_ok());
+
+    for (subcommand, command) in [
+        ("list-bots", Command::ListBots),
+        ("status", Command::Status),
+        ("validate-sql", Command::ValidateSql),
+    ] {
+        let cli = Cli::try_parse_from(["bulk_runner_rs", subcommand, "--bots-file", "bots.csv"]).unwrap();
+        assert_eq!(cli.command(), command);
+        assert!(!cli.dispatches());
+    }
+}
+
+#[tokio::test]
+async fn list_bots_and_status_show_every_bot_with_its_parsed_status() {
+    let dir = tempfile::tempdir().unwrap();
+    let bots_file = dir.path().join("bots.csv");
+    std::fs::write(&bots_file, BOTS_CSV).unwrap();
+
+    let cli = Cli::try_parse_from([
+        "bulk_runner_rs",
+        "list-bots",
+        "--bots-file",
+        bots_file.to_str().unwrap(),
+        "--extra-column",
+        "site",
+        "--limit_total_runnable",
+        "1",
+    ])
+    .unwrap();
+    let bots = read_bots(&*cli.bot_source().unwrap()).await.unwrap();
+
+    // Not ready bots, and those past the run limit, are listed too
+    assert_eq!(bots.len(), 5);
+
+    let listing = bots_table(&bots);
+    assert!(listing.contains("BOT02  PENDING     no         site=LON"), "{listing}");
+    assert!(listing.contains("BOT04  LOGGED OUT  yes        site=NYC"), "{listing}");
+    assert!(listing.ends_with("5 bot(s), 3 available"), "{listing}");
+
+    let status = status_table(&bots);
+    assert!(status.contains("IDLE        2      yes        BOT01, BOT05"), "{status}");
+    assert!(status.contains("OFFLINE     1      no         BOT03"), "{status}");
+    assert!(status.ends_with("5 resource(s), 3 available"), "{status}");
+}
+
+#[test]
+fn validate_sql_needs_the_name_and_status_columns_but_not_the_extras() {
+    let columns = ColumnMap::new("name", "status").with_extras(["site", "region"]);
+
+    let valid = SqlValidation::new(vec!["Name".into(), "STATUS".into(), "site".into()], &columns);
+    assert!(valid.is_valid());
+    assert!(valid.missing_required().is_empty());
+    assert_eq!(valid.missing_extras(), ["region"]);
+
+    let rendered = validation_table(&valid);
+    assert!(rendered.contains("bots are read without: region"), "{rendered}");
+    assert!(rendered.ends_with("the SQL file is valid"), "{rendered}");
+
+    let invalid = SqlValidation::new(vec!["name".into(), "state".into()], &columns);
+    assert!(!invalid.is_valid());
+    assert_eq!(invalid.missing_required(), ["status"]);
+    assert!(validation_table(&invalid).ends_with("the SQL file is invalid"));
+}
+
+#[test]
+fn a_missing_sql_file_fails_validate_sql_and_plan_with_its_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing.sql");
+    let missing = missing.to_str().unwrap();
+
+    let cli = Cli::try_parse_from(["bulk_runner_rs", "validate-sql", "-f", missing]).unwrap();
+    let Err(validate_error) = cli.sql_bot_source() else {
+        panic!("validate-sql read a SQL file that doesn't exist");
+    };
+
+    let cli = Cli::try_parse_from(["bulk_runner_rs", "plan", PROCESS, "-f", missing]).unwrap();
+    let Err(plan_error) = Runner::try_from(cli) else {
+        panic!("plan read a SQL file that doesn't exist");
+    };
+
+    for error in [validate_error, plan_error] {
+        assert!(matches!(&error, Error::SqlFile(path, _) if path.to_str() == Some(missing)), "{error}");
+        assert!(error.to_string().contains(missing), "{error}");
+        assert_eq!(RunExitCode::from(&error), RunExitCode::Error);
+    }
+}